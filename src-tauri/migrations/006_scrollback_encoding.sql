@@ -0,0 +1,3 @@
+-- 'utf8': scrollback is the raw text. 'base64': the output was not valid UTF-8 and
+-- scrollback holds the base64-encoded raw bytes.
+ALTER TABLE agent_sessions ADD COLUMN scrollback_encoding TEXT NOT NULL DEFAULT 'utf8';
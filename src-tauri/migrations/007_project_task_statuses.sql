@@ -0,0 +1,6 @@
+CREATE TABLE project_task_statuses (
+  project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+  status TEXT NOT NULL,
+  position INTEGER NOT NULL,
+  PRIMARY KEY (project_id, status)
+);
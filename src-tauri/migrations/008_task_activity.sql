@@ -0,0 +1,11 @@
+-- No FK on session_id: sessions are deleted when killed, the audit trail should outlive them.
+CREATE TABLE task_activity (
+  id TEXT PRIMARY KEY,
+  task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+  session_id TEXT,
+  kind TEXT NOT NULL,
+  content TEXT NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX idx_task_activity_task_id ON task_activity(task_id);
@@ -0,0 +1,6 @@
+-- Opaque frontend layout (open terminals, sizes, positions); removed with the project.
+CREATE TABLE project_layouts (
+  project_id TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+  layout TEXT NOT NULL,
+  updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
@@ -0,0 +1,5 @@
+-- How each session was launched (JSON `SpawnInfo`, env already redacted); removed with the session.
+CREATE TABLE session_spawn_info (
+  session_id TEXT PRIMARY KEY REFERENCES agent_sessions(id) ON DELETE CASCADE,
+  info TEXT NOT NULL
+);
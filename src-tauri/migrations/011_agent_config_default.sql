@@ -0,0 +1,35 @@
+-- Exactly one agent config is the default whenever any exist.
+
+-- Repair existing data: keep the oldest default, or promote the oldest config if none is.
+UPDATE agent_configs SET is_default = 0
+WHERE is_default = 1 AND id != (
+  SELECT id FROM agent_configs WHERE is_default = 1 ORDER BY created_at ASC, id ASC LIMIT 1
+);
+UPDATE agent_configs SET is_default = 1
+WHERE NOT EXISTS (SELECT 1 FROM agent_configs WHERE is_default = 1)
+  AND id = (SELECT id FROM agent_configs ORDER BY created_at ASC, id ASC LIMIT 1);
+
+-- The first config added becomes the default.
+CREATE TRIGGER agent_configs_default_on_insert
+AFTER INSERT ON agent_configs
+WHEN NOT EXISTS (SELECT 1 FROM agent_configs WHERE is_default = 1)
+BEGIN
+  UPDATE agent_configs SET is_default = 1 WHERE id = NEW.id;
+END;
+
+-- A config inserted as the default takes over from the previous one.
+CREATE TRIGGER agent_configs_single_default_on_insert
+AFTER INSERT ON agent_configs
+WHEN NEW.is_default = 1
+BEGIN
+  UPDATE agent_configs SET is_default = 0 WHERE id != NEW.id AND is_default = 1;
+END;
+
+-- Deleting the default promotes the oldest remaining config.
+CREATE TRIGGER agent_configs_default_on_delete
+AFTER DELETE ON agent_configs
+WHEN OLD.is_default = 1
+BEGIN
+  UPDATE agent_configs SET is_default = 1
+  WHERE id = (SELECT id FROM agent_configs ORDER BY created_at ASC, id ASC LIMIT 1);
+END;
@@ -0,0 +1,20 @@
+-- Explicit ordering for the spawn menu, backfilled from the previous default-first, oldest-first order.
+ALTER TABLE agent_configs ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+UPDATE agent_configs SET position = (
+  SELECT COUNT(*) FROM agent_configs other
+  WHERE other.is_default > agent_configs.is_default
+     OR (other.is_default = agent_configs.is_default
+         AND (other.created_at < agent_configs.created_at
+              OR (other.created_at = agent_configs.created_at AND other.id < agent_configs.id)))
+);
+
+-- Deleting the default now promotes the first config in menu order.
+DROP TRIGGER agent_configs_default_on_delete;
+CREATE TRIGGER agent_configs_default_on_delete
+AFTER DELETE ON agent_configs
+WHEN OLD.is_default = 1
+BEGIN
+  UPDATE agent_configs SET is_default = 1
+  WHERE id = (SELECT id FROM agent_configs ORDER BY position ASC, created_at ASC LIMIT 1);
+END;
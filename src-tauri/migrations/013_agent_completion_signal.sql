@@ -0,0 +1,3 @@
+-- Optional "done with the task" signals for agents that stay running after finishing.
+ALTER TABLE agent_configs ADD COLUMN completion_marker TEXT;
+ALTER TABLE agent_configs ADD COLUMN sentinel_file TEXT;
@@ -0,0 +1,2 @@
+-- The agent config a session was launched from, if any. Kept as history when the config is deleted.
+ALTER TABLE agent_sessions ADD COLUMN agent_config_id TEXT REFERENCES agent_configs(id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- Bytes a session produced and received, saved when it stops.
+ALTER TABLE agent_sessions ADD COLUMN bytes_out INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE agent_sessions ADD COLUMN bytes_in INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Last terminal size, restored before replaying scrollback.
+ALTER TABLE agent_sessions ADD COLUMN cols INTEGER NOT NULL DEFAULT 80;
+ALTER TABLE agent_sessions ADD COLUMN rows INTEGER NOT NULL DEFAULT 24;
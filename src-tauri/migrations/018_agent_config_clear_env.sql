@@ -0,0 +1,3 @@
+-- Start the agent from a minimal environment instead of inheriting the app's (see
+-- `pty_manager::ISOLATED_ENV_VARS`).
+ALTER TABLE agent_configs ADD COLUMN clear_env INTEGER NOT NULL DEFAULT 0;
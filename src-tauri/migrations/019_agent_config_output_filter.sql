@@ -0,0 +1,2 @@
+-- Shell command the agent's output is piped through before it reaches the terminal.
+ALTER TABLE agent_configs ADD COLUMN output_filter TEXT;
@@ -11,11 +11,88 @@ pub struct AgentConfig {
     pub args: String, // JSON array e.g. '["--flag"]'
     pub is_default: bool,
     pub created_at: i64,
+    /// Order in the spawn menu, after the default config.
+    pub position: i64,
+    /// Text the agent prints when it has finished its task.
+    pub completion_marker: Option<String>,
+    /// Path, relative to the project, the agent creates when it has finished its task.
+    pub sentinel_file: Option<String>,
+    /// Start from a minimal environment instead of inheriting the app's.
+    pub clear_env: bool,
+    /// Shell command the agent's output is piped through before it reaches the terminal.
+    pub output_filter: Option<String>,
+}
+
+/// Args as sent by the frontend: either already split, or a single command-line string
+/// using shell quoting rules (`--model "gpt 4" -v`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ArgsInput {
+    List(Vec<String>),
+    Line(String),
+}
+
+impl ArgsInput {
+    pub fn into_argv(self) -> Result<Vec<String>> {
+        match self {
+            ArgsInput::List(args) => Ok(args),
+            ArgsInput::Line(line) => parse_command_line_args(&line),
+        }
+    }
+}
+
+pub fn parse_command_line_args(line: &str) -> Result<Vec<String>> {
+    shell_words::split(line).map_err(|e| anyhow::anyhow!("Invalid command line: {}", e))
+}
+
+/// Expand `${VAR}` and `$VAR` in `arg` using `lookup`; `$$` is a literal `$`. Unknown
+/// variables, and an unclosed `${`, are left as written, or rejected when `strict` is set.
+pub fn expand_env_vars(arg: &str, lookup: impl Fn(&str) -> Option<String>, strict: bool) -> Result<String> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let (name, written, tail) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &rest[i..i + end + 3], &braced[end + 1..]),
+                None if strict => return Err(anyhow::anyhow!("Unclosed ${{ in argument: {}", arg)),
+                None => {
+                    out.push_str(&rest[i..]);
+                    return Ok(out);
+                }
+            }
+        } else if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+            continue;
+        } else {
+            let end = after.find(|c: char| !is_name_char(c)).unwrap_or(after.len());
+            (&after[..end], &rest[i..i + end + 1], &after[end..])
+        };
+        let is_name = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(is_name_char);
+        if !is_name {
+            // Not a variable reference (e.g. a lone `$` or `$1`); keep it as written.
+            out.push_str(written);
+        } else if let Some(value) = lookup(name) {
+            out.push_str(&value);
+        } else if strict {
+            return Err(anyhow::anyhow!("Environment variable {} is not set", name));
+        } else {
+            out.push_str(written);
+        }
+        rest = tail;
+    }
+    out.push_str(rest);
+    Ok(out)
 }
 
 pub async fn list_db(pool: &SqlitePool) -> Result<Vec<AgentConfig>> {
     Ok(sqlx::query_as::<_, AgentConfig>(
-        "SELECT * FROM agent_configs ORDER BY is_default DESC, created_at ASC",
+        "SELECT * FROM agent_configs ORDER BY is_default DESC, position ASC",
     )
     .fetch_all(pool)
     .await?)
@@ -24,7 +101,9 @@ pub async fn list_db(pool: &SqlitePool) -> Result<Vec<AgentConfig>> {
 pub async fn add_db(pool: &SqlitePool, name: &str, command: &str, args: &str) -> Result<AgentConfig> {
     let id = Uuid::new_v4().to_string();
     Ok(sqlx::query_as::<_, AgentConfig>(
-        "INSERT INTO agent_configs (id, name, command, args) VALUES (?, ?, ?, ?) RETURNING *",
+        "INSERT INTO agent_configs (id, name, command, args, position)
+         VALUES (?, ?, ?, ?, (SELECT COALESCE(MAX(position) + 1, 0) FROM agent_configs))
+         RETURNING *",
     )
     .bind(&id)
     .bind(name)
@@ -45,20 +124,118 @@ pub async fn update_db(pool: &SqlitePool, id: &str, name: &str, command: &str, a
     Ok(())
 }
 
+/// Deleting the default promotes the first remaining config in menu order (see migrations
+/// 011/012, whose triggers keep exactly one default whenever any config exists). The last
+/// remaining config can't be deleted.
 pub async fn delete_db(pool: &SqlitePool, id: &str) -> Result<()> {
-    // Prevent deleting the last config or the builtin default
-    sqlx::query("DELETE FROM agent_configs WHERE id = ? AND is_default = 0")
+    let deleted = sqlx::query(
+        "DELETE FROM agent_configs WHERE id = ? AND (SELECT COUNT(*) FROM agent_configs) > 1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?
+    .rows_affected();
+    if deleted == 0 {
+        let exists = sqlx::query("SELECT 1 FROM agent_configs WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .is_some();
+        if exists {
+            return Err(anyhow::anyhow!("Cannot delete the last agent config"));
+        }
+    }
+    Ok(())
+}
+
+/// Move a config to `position` (clamped to the end) and renumber the rest.
+pub async fn reorder_db(pool: &SqlitePool, id: &str, position: usize) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let mut ids: Vec<String> =
+        sqlx::query_as::<_, (String,)>("SELECT id FROM agent_configs ORDER BY position, created_at")
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+    let from = ids
+        .iter()
+        .position(|c| c == id)
+        .ok_or_else(|| anyhow::anyhow!("Agent config not found"))?;
+    let moved = ids.remove(from);
+    ids.insert(position.min(ids.len()), moved);
+    for (position, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE agent_configs SET position = ? WHERE id = ?")
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// `true` starts the config's agents from `pty_manager::isolated_env`.
+pub async fn set_clear_env_db(pool: &SqlitePool, id: &str, clear_env: bool) -> Result<()> {
+    let result = sqlx::query("UPDATE agent_configs SET clear_env = ? WHERE id = ?")
+        .bind(clear_env)
         .bind(id)
         .execute(pool)
         .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Agent config not found"));
+    }
     Ok(())
 }
 
-pub async fn set_default_db(pool: &SqlitePool, id: &str) -> Result<()> {
-    sqlx::query("UPDATE agent_configs SET is_default = CASE WHEN id = ? THEN 1 ELSE 0 END")
+/// An empty `output_filter` clears it, like `None`.
+pub async fn set_output_filter_db(pool: &SqlitePool, id: &str, output_filter: Option<&str>) -> Result<()> {
+    let result = sqlx::query("UPDATE agent_configs SET output_filter = NULLIF(TRIM(?), '') WHERE id = ?")
+        .bind(output_filter)
         .bind(id)
         .execute(pool)
         .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Agent config not found"));
+    }
+    Ok(())
+}
+
+/// Empty values clear the signal.
+pub async fn set_completion_signal_db(
+    pool: &SqlitePool,
+    id: &str,
+    completion_marker: Option<&str>,
+    sentinel_file: Option<&str>,
+) -> Result<()> {
+    let result = sqlx::query(
+        "UPDATE agent_configs SET completion_marker = NULLIF(?, ''), sentinel_file = NULLIF(?, '')
+         WHERE id = ?",
+    )
+    .bind(completion_marker)
+    .bind(sentinel_file)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Agent config not found"));
+    }
+    Ok(())
+}
+
+pub async fn set_default_db(pool: &SqlitePool, id: &str) -> Result<()> {
+    // Guarded so an unknown id can't clear the flag on every row.
+    let result = sqlx::query(
+        "UPDATE agent_configs SET is_default = CASE WHEN id = ? THEN 1 ELSE 0 END
+         WHERE EXISTS (SELECT 1 FROM agent_configs WHERE id = ?)",
+    )
+    .bind(id)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Agent config not found"));
+    }
     Ok(())
 }
 
@@ -68,18 +245,24 @@ pub async fn set_default_db(pool: &SqlitePool, id: &str) -> Result<()> {
 pub async fn list_agent_configs(
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Vec<AgentConfig>, String> {
-    list_db(&state.db).await.map_err(|e| e.to_string())
+    list_db(&state.db()).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn parse_command_line(line: String) -> Result<Vec<String>, String> {
+    parse_command_line_args(&line).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn add_agent_config(
     name: String,
     command: String,
-    args: Vec<String>,
+    args: ArgsInput,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<AgentConfig, String> {
+    let args = args.into_argv().map_err(|e| e.to_string())?;
     let args_json = serde_json::to_string(&args).unwrap_or_else(|_| "[]".to_string());
-    add_db(&state.db, &name, &command, &args_json)
+    add_db(&state.db(), &name, &command, &args_json)
         .await
         .map_err(|e| e.to_string())
 }
@@ -89,11 +272,12 @@ pub async fn update_agent_config(
     id: String,
     name: String,
     command: String,
-    args: Vec<String>,
+    args: ArgsInput,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
+    let args = args.into_argv().map_err(|e| e.to_string())?;
     let args_json = serde_json::to_string(&args).unwrap_or_else(|_| "[]".to_string());
-    update_db(&state.db, &id, &name, &command, &args_json)
+    update_db(&state.db(), &id, &name, &command, &args_json)
         .await
         .map_err(|e| e.to_string())
 }
@@ -103,7 +287,7 @@ pub async fn delete_agent_config(
     id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    delete_db(&state.db, &id).await.map_err(|e| e.to_string())
+    delete_db(&state.db(), &id).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -111,7 +295,231 @@ pub async fn set_default_agent_config(
     id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    set_default_db(&state.db, &id)
+    set_default_db(&state.db(), &id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reorder_agent_config(
+    id: String,
+    position: usize,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    reorder_db(&state.db(), &id, position)
         .await
         .map_err(|e| e.to_string())
 }
+
+/// How long `test_agent_config` watches the command before killing it.
+const TRIAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Launch a command briefly in a throwaway PTY to check it starts, without creating a
+/// session. Meant for the config editor, before the config is saved.
+#[tauri::command]
+pub async fn test_agent_config(
+    command: String,
+    args: ArgsInput,
+) -> Result<crate::pty_manager::TrialRun, String> {
+    let args = args.into_argv().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || crate::pty_manager::trial_run(&command, &args, TRIAL_TIMEOUT))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Relative and free of `..`, so the path can't point outside the project it is joined to.
+pub fn check_sentinel_path(path: &str) -> Result<(), String> {
+    let path = std::path::Path::new(path);
+    if path.is_absolute() || path.components().any(|c| c == std::path::Component::ParentDir) {
+        return Err("Sentinel file must be a path inside the project".to_string());
+    }
+    Ok(())
+}
+
+/// The sentinel path must stay inside the project, since it is deleted each time it fires.
+#[tauri::command]
+pub async fn set_agent_completion_signal(
+    id: String,
+    completion_marker: Option<String>,
+    sentinel_file: Option<String>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    if let Some(path) = sentinel_file.as_deref() {
+        check_sentinel_path(path)?;
+    }
+    set_completion_signal_db(&state.db(), &id, completion_marker.as_deref(), sentinel_file.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// See `pty_manager::ISOLATED_ENV_VARS` for what an isolated agent still gets.
+#[tauri::command]
+pub async fn set_agent_clear_env(
+    id: String,
+    clear_env: bool,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    set_clear_env_db(&state.db(), &id, clear_env)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `output_filter` runs in the platform shell, e.g. `jq --unbuffered .`; `None` removes it.
+#[tauri::command]
+pub async fn set_agent_output_filter(
+    id: String,
+    output_filter: Option<String>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    set_output_filter_db(&state.db(), &id, output_filter.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use tempfile::tempdir;
+
+    async fn default_ids(pool: &SqlitePool) -> Vec<String> {
+        list_db(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|c| c.is_default)
+            .map(|c| c.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_first_config_becomes_default() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("DELETE FROM agent_configs").execute(&pool).await.unwrap();
+
+        let first = add_db(&pool, "First", "first", "[]").await.unwrap();
+        let second = add_db(&pool, "Second", "second", "[]").await.unwrap();
+        assert_eq!(default_ids(&pool).await, [first.id.as_str()]);
+
+        set_default_db(&pool, &second.id).await.unwrap();
+        assert_eq!(default_ids(&pool).await, [second.id]);
+        assert!(set_default_db(&pool, "missing").await.is_err());
+        assert_eq!(default_ids(&pool).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_configs() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let a = add_db(&pool, "A", "a", "[]").await.unwrap();
+        let b = add_db(&pool, "B", "b", "[]").await.unwrap();
+        let c = add_db(&pool, "C", "c", "[]").await.unwrap();
+        assert_eq!((a.position, b.position, c.position), (1, 2, 3));
+
+        reorder_db(&pool, &c.id, 1).await.unwrap();
+        let names: Vec<String> = list_db(&pool).await.unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Claude Code", "C", "A", "B"]);
+
+        // The default stays first whatever its position.
+        reorder_db(&pool, "builtin-claude", 99).await.unwrap();
+        let names: Vec<String> = list_db(&pool).await.unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Claude Code", "C", "A", "B"]);
+        assert!(reorder_db(&pool, "missing", 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deleting_default_promotes_oldest() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let other = add_db(&pool, "Other", "other", "[]").await.unwrap();
+        assert_eq!(default_ids(&pool).await, ["builtin-claude"]);
+
+        delete_db(&pool, "builtin-claude").await.unwrap();
+        assert_eq!(default_ids(&pool).await, [other.id.as_str()]);
+
+        let err = delete_db(&pool, &other.id).await.unwrap_err();
+        assert_eq!(err.to_string(), "Cannot delete the last agent config");
+        assert_eq!(default_ids(&pool).await, [other.id.as_str()]);
+    }
+
+    #[tokio::test]
+    async fn test_completion_signal_set_and_cleared() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let config = add_db(&pool, "A", "a", "[]").await.unwrap();
+        assert_eq!(config.completion_marker, None);
+
+        set_completion_signal_db(&pool, &config.id, Some("<<DONE>>"), Some(".spawn/done"))
+            .await
+            .unwrap();
+        let saved = list_db(&pool).await.unwrap().into_iter().find(|c| c.id == config.id).unwrap();
+        assert_eq!(saved.completion_marker.as_deref(), Some("<<DONE>>"));
+        assert_eq!(saved.sentinel_file.as_deref(), Some(".spawn/done"));
+
+        set_completion_signal_db(&pool, &config.id, Some(""), None).await.unwrap();
+        let saved = list_db(&pool).await.unwrap().into_iter().find(|c| c.id == config.id).unwrap();
+        assert_eq!((saved.completion_marker, saved.sentinel_file), (None, None));
+        assert!(set_completion_signal_db(&pool, "missing", None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clear_env_defaults_off() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let config = add_db(&pool, "A", "a", "[]").await.unwrap();
+        assert!(!config.clear_env);
+
+        set_clear_env_db(&pool, &config.id, true).await.unwrap();
+        let saved = list_db(&pool).await.unwrap().into_iter().find(|c| c.id == config.id).unwrap();
+        assert!(saved.clear_env);
+        assert!(set_clear_env_db(&pool, "missing", true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_output_filter_set_and_cleared() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let config = add_db(&pool, "A", "a", "[]").await.unwrap();
+        assert_eq!(config.output_filter, None);
+
+        set_output_filter_db(&pool, &config.id, Some("jq .")).await.unwrap();
+        let saved = list_db(&pool).await.unwrap().into_iter().find(|c| c.id == config.id).unwrap();
+        assert_eq!(saved.output_filter.as_deref(), Some("jq ."));
+
+        set_output_filter_db(&pool, &config.id, Some("  ")).await.unwrap();
+        let saved = list_db(&pool).await.unwrap().into_iter().find(|c| c.id == config.id).unwrap();
+        assert_eq!(saved.output_filter, None);
+        assert!(set_output_filter_db(&pool, "missing", None).await.is_err());
+    }
+
+    #[test]
+    fn test_check_sentinel_path() {
+        assert!(check_sentinel_path(".spawn/done").is_ok());
+        assert!(check_sentinel_path("/etc/passwd").is_err());
+        assert!(check_sentinel_path("../other/done").is_err());
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |name: &str| (name == "MODEL").then(|| "opus".to_string());
+        assert_eq!(expand_env_vars("--model=${MODEL}", lookup, false).unwrap(), "--model=opus");
+        assert_eq!(expand_env_vars("$MODEL-x $$MODEL", lookup, false).unwrap(), "opus-x $MODEL");
+        assert_eq!(expand_env_vars("$UNSET ${UNSET} $1 $", lookup, false).unwrap(), "$UNSET ${UNSET} $1 $");
+        assert!(expand_env_vars("${UNSET}", lookup, true).is_err());
+        assert_eq!(expand_env_vars("$MODEL ${MODEL", lookup, false).unwrap(), "opus ${MODEL");
+        assert!(expand_env_vars("${MODEL", lookup, true).is_err());
+    }
+
+    #[test]
+    fn test_args_input_accepts_list_or_quoted_line() {
+        let list: ArgsInput = serde_json::from_str(r#"["--model", "gpt 4"]"#).unwrap();
+        assert_eq!(list.into_argv().unwrap(), ["--model", "gpt 4"]);
+
+        let line: ArgsInput = serde_json::from_str(r#""--model 'gpt 4' -v""#).unwrap();
+        assert_eq!(line.into_argv().unwrap(), ["--model", "gpt 4", "-v"]);
+
+        assert!(parse_command_line_args("--prompt \"unterminated").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+/// Strip ANSI escape sequences (CSI, OSC and two-byte ESC sequences) from terminal output,
+/// leaving only the printable text.
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: ESC [ params... final byte in 0x40..=0x7E
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: ESC ] ... terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The last `n` lines of terminal output with escape sequences removed, joined by `\n`.
+/// Only the tail of `output` is decoded, so this stays cheap for multi-megabyte buffers.
+/// Trailing blank lines (e.g. the cursor sitting on an empty line) don't count.
+pub fn tail_lines(output: &[u8], n: usize) -> String {
+    if n == 0 {
+        return String::new();
+    }
+    let end = output
+        .iter()
+        .rposition(|b| !b"\r\n \t".contains(b))
+        .map_or(0, |i| i + 1);
+    let start = output[..end]
+        .iter()
+        .rev()
+        .enumerate()
+        .filter(|(_, &b)| b == b'\n')
+        .nth(n - 1)
+        .map_or(0, |(i, _)| end - i);
+    let text = strip_ansi(&String::from_utf8_lossy(&output[start..end]));
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end_matches('\r')).collect();
+    lines[lines.len().saturating_sub(n)..].join("\n")
+}
+
+/// Titles longer than this are truncated; nothing legitimate needs more.
+const MAX_TITLE_BYTES: usize = 256;
+
+#[derive(Default)]
+enum TitleState {
+    #[default]
+    Ground,
+    Esc,
+    /// Inside `ESC ]`, reading the numeric parameter before `;`.
+    Param(Vec<u8>),
+    /// Inside an OSC body; `true` when it is a title (OSC 0 or 2) worth keeping.
+    Body(bool),
+    /// Saw ESC inside the body; `\` completes the ST terminator.
+    BodyEsc(bool),
+}
+
+/// Picks window-title sequences (`ESC ] 0 ; title BEL` / `ESC ] 2 ; title ESC \`) out of a
+/// byte stream, including ones split across chunks. Other OSC sequences are ignored.
+#[derive(Default)]
+pub struct TitleScanner {
+    state: TitleState,
+    title: Vec<u8>,
+}
+
+impl TitleScanner {
+    /// The last title completed within `data`, if any.
+    pub fn feed(&mut self, data: &[u8]) -> Option<String> {
+        let mut found = None;
+        for &b in data {
+            self.state = match std::mem::take(&mut self.state) {
+                TitleState::Ground if b == 0x1b => TitleState::Esc,
+                TitleState::Ground => TitleState::Ground,
+                TitleState::Esc if b == b']' => TitleState::Param(Vec::new()),
+                TitleState::Esc if b == 0x1b => TitleState::Esc,
+                TitleState::Esc => TitleState::Ground,
+                TitleState::Param(mut param) => match b {
+                    b';' => {
+                        self.title.clear();
+                        TitleState::Body(param == b"0" || param == b"2")
+                    }
+                    b'0'..=b'9' if param.len() < 4 => {
+                        param.push(b);
+                        TitleState::Param(param)
+                    }
+                    0x07 => TitleState::Ground,
+                    0x1b => TitleState::BodyEsc(false),
+                    _ => TitleState::Body(false),
+                },
+                TitleState::Body(is_title) => match b {
+                    0x07 => {
+                        if is_title {
+                            found = Some(self.take_title());
+                        }
+                        TitleState::Ground
+                    }
+                    0x1b => TitleState::BodyEsc(is_title),
+                    _ => {
+                        if is_title && self.title.len() < MAX_TITLE_BYTES {
+                            self.title.push(b);
+                        }
+                        TitleState::Body(is_title)
+                    }
+                },
+                TitleState::BodyEsc(is_title) => {
+                    if b == b'\\' && is_title {
+                        found = Some(self.take_title());
+                    }
+                    // Any other byte aborts the sequence, as in a real terminal.
+                    if b == 0x1b {
+                        TitleState::Esc
+                    } else {
+                        TitleState::Ground
+                    }
+                }
+            };
+        }
+        found
+    }
+
+    fn take_title(&mut self) -> String {
+        let title = String::from_utf8_lossy(&self.title).trim().to_string();
+        self.title.clear();
+        title
+    }
+}
+
+/// Decodes a byte stream chunk by chunk, holding back a UTF-8 sequence split across chunk
+/// boundaries instead of turning each half into U+FFFD. Invalid bytes are still replaced.
+#[derive(Default)]
+pub struct Utf8Stream {
+    pending: Vec<u8>,
+}
+
+impl Utf8Stream {
+    pub fn push(&mut self, data: &[u8]) -> String {
+        self.pending.extend_from_slice(data);
+        let complete = self.pending.len() - incomplete_tail_len(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        text
+    }
+}
+
+/// Length of a trailing multi-byte sequence that has started but not finished.
+fn incomplete_tail_len(bytes: &[u8]) -> usize {
+    for i in 1..=bytes.len().min(3) {
+        let b = bytes[bytes.len() - i];
+        if b & 0xC0 == 0x80 {
+            continue; // continuation byte, keep looking for the lead byte
+        }
+        let needed = match b {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if needed > i { i } else { 0 };
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_csi_colors() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m done"), "ok done");
+    }
+
+    #[test]
+    fn test_strip_osc_title() {
+        assert_eq!(strip_ansi("\x1b]0;my title\x07prompt$ "), "prompt$ ");
+        assert_eq!(strip_ansi("\x1b]2;t\x1b\\x"), "x");
+    }
+
+    #[test]
+    fn test_plain_text_unchanged() {
+        assert_eq!(strip_ansi("hello\nworld"), "hello\nworld");
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let out = b"one\r\n\x1b[32mtwo\x1b[0m\r\n\r\nfour\r\n\r\n";
+        assert_eq!(tail_lines(out, 3), "two\n\nfour");
+        assert_eq!(tail_lines(out, 1), "four");
+        assert_eq!(tail_lines(out, 10), "one\ntwo\n\nfour");
+        assert_eq!(tail_lines(out, 0), "");
+        assert_eq!(tail_lines(b"", 5), "");
+    }
+
+    #[test]
+    fn test_title_scanner() {
+        let mut scanner = TitleScanner::default();
+        assert_eq!(scanner.feed(b"\x1b]0;npm test\x07$ ").as_deref(), Some("npm test"));
+        assert_eq!(scanner.feed(b"\x1b]2;a\x07\x1b]2;b\x1b\\").as_deref(), Some("b"));
+        // Icon names and other OSC sequences are not titles.
+        assert_eq!(scanner.feed(b"\x1b]1;icon\x07\x1b]8;;http://x\x07\x1b[1mhi"), None);
+    }
+
+    #[test]
+    fn test_title_scanner_across_chunks() {
+        let mut scanner = TitleScanner::default();
+        let mut found = None;
+        for chunk in "\x1b]0;café build\x1b\\".as_bytes().chunks(3) {
+            found = scanner.feed(chunk).or(found);
+        }
+        assert_eq!(found.as_deref(), Some("café build"));
+    }
+
+    #[test]
+    fn test_utf8_stream_joins_split_sequences() {
+        let bytes = "é€😀".as_bytes();
+        let mut stream = Utf8Stream::default();
+        let mut out = String::new();
+        for b in bytes {
+            out.push_str(&stream.push(&[*b]));
+        }
+        assert_eq!(out, "é€😀");
+        assert_eq!(stream.push(b"a\xffb"), "a\u{fffd}b");
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use serde::Serialize;
 use sqlx::{SqlitePool, sqlite::{SqlitePoolOptions, SqliteConnectOptions}};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::str::FromStr;
 
@@ -20,6 +22,61 @@ pub async fn init(data_dir: &Path) -> Result<SqlitePool> {
     Ok(pool)
 }
 
+#[derive(Debug, Serialize)]
+pub struct DatabaseHealth {
+    /// `PRAGMA integrity_check` passed.
+    pub ok: bool,
+    /// Problems reported by the integrity check; empty when `ok`.
+    pub problems: Vec<String>,
+    pub path: String,
+    pub file_size: u64,
+    /// `-wal` file size, when the database is in WAL mode and the file exists.
+    pub wal_size: Option<u64>,
+    pub journal_mode: String,
+    pub table_rows: BTreeMap<String, i64>,
+}
+
+/// Integrity check, file sizes and row counts. Only reads, so it is safe on the live pool.
+pub async fn health(pool: &SqlitePool) -> Result<DatabaseHealth> {
+    let results: Vec<(String,)> = sqlx::query_as("PRAGMA integrity_check").fetch_all(pool).await?;
+    let problems: Vec<String> = results.into_iter().map(|(r,)| r).filter(|r| r != "ok").collect();
+    let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode").fetch_one(pool).await?;
+    let (path,): (String,) =
+        sqlx::query_as("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_one(pool)
+            .await?;
+    let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let wal_size = std::fs::metadata(format!("{}-wal", path)).ok().map(|m| m.len());
+
+    let tables: Vec<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut table_rows = BTreeMap::new();
+    for (table,) in tables {
+        let query = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+        let (count,): (i64,) = sqlx::query_as(&query).fetch_one(pool).await?;
+        table_rows.insert(table, count);
+    }
+    Ok(DatabaseHealth {
+        ok: problems.is_empty(),
+        problems,
+        path,
+        file_size,
+        wal_size,
+        journal_mode,
+        table_rows,
+    })
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub async fn database_health(state: tauri::State<'_, crate::AppState>) -> Result<DatabaseHealth, String> {
+    health(&state.db()).await.map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,7 +99,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let pool = init(dir.path()).await.unwrap();
 
-        for table in &["projects", "agent_sessions", "tasks", "project_groups"] {
+        for table in &["projects", "agent_sessions", "tasks", "project_groups", "settings", "project_task_statuses", "task_activity", "project_layouts", "session_spawn_info"] {
             let count: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
                 .fetch_one(&pool)
                 .await
@@ -50,4 +107,21 @@ mod tests {
             assert_eq!(count.0, 0);
         }
     }
+
+    #[tokio::test]
+    async fn test_health_reports_counts() {
+        let dir = tempdir().unwrap();
+        let pool = init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let health = health(&pool).await.unwrap();
+        assert!(health.ok, "{:?}", health.problems);
+        assert!(health.file_size > 0);
+        assert_eq!(health.table_rows["projects"], 1);
+        assert_eq!(health.table_rows["tasks"], 0);
+        assert!(!health.journal_mode.is_empty());
+    }
 }
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Walks stop after this many files so a huge monorepo can't balloon memory.
+pub const MAX_INDEXED_PATHS: usize = 50_000;
+
+/// Directories never descended into, on top of whatever `.gitignore` excludes.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist"];
+
+/// Files of one project, relative to its root and sorted.
+#[derive(Debug, Default, Serialize)]
+pub struct ProjectFiles {
+    pub files: Vec<PathBuf>,
+    /// Whether the walk stopped at `MAX_INDEXED_PATHS`.
+    pub truncated: bool,
+}
+
+impl ProjectFiles {
+    /// Walk `root`, skipping hidden and build directories and gitignored paths. Symlinks are not followed,
+    /// so the walk never leaves the project.
+    pub fn build(root: &Path) -> Self {
+        Self::build_with_limit(root, MAX_INDEXED_PATHS)
+    }
+
+    fn build_with_limit(root: &Path, limit: usize) -> Self {
+        let repo = git2::Repository::open(root).ok();
+        let ignored = |rel: &Path| {
+            repo.as_ref()
+                .is_some_and(|r| r.is_path_ignored(rel).unwrap_or(false))
+        };
+        let mut index = Self::default();
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let Ok(file_type) = entry.file_type() else { continue };
+                let path = entry.path();
+                let Ok(rel) = path.strip_prefix(root) else { continue };
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if file_type.is_dir() {
+                    let skipped = name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref());
+                    if !skipped && !ignored(rel) {
+                        stack.push(path);
+                    }
+                } else if file_type.is_file() && !ignored(rel) {
+                    if index.files.len() == limit {
+                        index.truncated = true;
+                        index.files.sort();
+                        return index;
+                    }
+                    index.files.push(rel.to_path_buf());
+                }
+            }
+        }
+        index.files.sort();
+        index
+    }
+
+    pub fn markdown_files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files
+            .iter()
+            .filter(|f| f.to_string_lossy().to_lowercase().ends_with(".md"))
+    }
+
+    pub fn contains(&self, rel: &Path) -> bool {
+        self.files.binary_search_by(|f| f.as_path().cmp(rel)).is_ok()
+    }
+}
+
+/// In-memory file listings per project. Entries are built lazily and dropped by the git
+/// watcher whenever the project's files change, so only watched projects are cached.
+#[derive(Default)]
+pub struct FileIndex {
+    projects: Mutex<HashMap<String, Arc<ProjectFiles>>>,
+}
+
+impl FileIndex {
+    pub fn get_or_build(&self, project_id: &str, root: &Path) -> Arc<ProjectFiles> {
+        if let Some(files) = self.projects.lock().unwrap().get(project_id) {
+            return Arc::clone(files);
+        }
+        // Walk without holding the lock; a concurrent build of the same project is harmless.
+        let files = Arc::new(ProjectFiles::build(root));
+        self.projects
+            .lock()
+            .unwrap()
+            .insert(project_id.to_string(), Arc::clone(&files));
+        files
+    }
+
+    pub fn invalidate(&self, project_id: &str) {
+        self.projects.lock().unwrap().remove(project_id);
+    }
+
+    pub fn clear(&self) {
+        self.projects.lock().unwrap().clear();
+    }
+}
+
+/// The project's listing: cached while the project is watched (the watcher keeps the cache
+/// fresh), walked from scratch otherwise.
+fn project_files(state: &crate::AppState, project_id: &str, root: &Path) -> Arc<ProjectFiles> {
+    if state.git_watcher.is_watching(project_id) {
+        state.file_index.get_or_build(project_id, root)
+    } else {
+        Arc::new(ProjectFiles::build(root))
+    }
+}
+
+// --- Tauri commands ---
+
+/// Markdown files in the project, relative to its root.
+#[tauri::command]
+pub fn list_markdown_files(
+    project_id: String,
+    project_path: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<String>, String> {
+    let root = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path: {}", e))?;
+    let files = project_files(&state, &project_id, &root);
+    Ok(files
+        .markdown_files()
+        .map(|f| f.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Read a file by its path relative to the project root. Only files in the project's
+/// listing can be read, so ignored, hidden-directory and out-of-project paths are refused.
+#[tauri::command]
+pub fn read_project_file(
+    project_id: String,
+    project_path: String,
+    path: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<String, String> {
+    let root = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path: {}", e))?;
+    let files = project_files(&state, &project_id, &root);
+    if !files.contains(Path::new(&path)) {
+        return Err(format!("{} is not a file in this project", path));
+    }
+    std::fs::read_to_string(root.join(&path)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_build_respects_gitignore_and_limit() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git2::Repository::init(root).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n*.log\n").unwrap();
+        for path in ["README.md", "docs/guide.md", "src/main.rs", "generated/out.md", "debug.log"] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        let index = ProjectFiles::build(root);
+        let files: Vec<String> =
+            index.files.iter().map(|f| f.to_string_lossy().into_owned()).collect();
+        assert_eq!(files, [".gitignore", "README.md", "docs/guide.md", "src/main.rs"]);
+        assert!(!index.truncated);
+        assert_eq!(index.markdown_files().count(), 2);
+        assert!(index.contains(Path::new("docs/guide.md")));
+        assert!(!index.contains(Path::new("generated/out.md")));
+
+        let capped = ProjectFiles::build_with_limit(root, 2);
+        assert_eq!(capped.files.len(), 2);
+        assert!(capped.truncated);
+    }
+
+    #[test]
+    fn test_index_caches_until_invalidated() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "").unwrap();
+        let index = FileIndex::default();
+        assert_eq!(index.get_or_build("p1", dir.path()).files.len(), 1);
+
+        std::fs::write(dir.path().join("b.md"), "").unwrap();
+        assert_eq!(index.get_or_build("p1", dir.path()).files.len(), 1);
+        index.invalidate("p1");
+        assert_eq!(index.get_or_build("p1", dir.path()).files.len(), 2);
+    }
+}
@@ -1,5 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub is_current: bool,
+    /// False for branches that need `--set-upstream` on their first push.
+    pub has_upstream: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitStatus {
     pub is_git_repo: bool,
@@ -10,13 +18,17 @@ pub struct GitStatus {
     pub changed_files: usize,
     pub staged_files: usize,
     pub last_commit: Option<String>,
+    /// Branch names only; kept for existing callers, prefer `branches`.
     pub local_branches: Vec<String>,
+    pub branches: Vec<BranchInfo>,
+    /// Set when the repo exists but couldn't be opened or read (corrupt index, permissions),
+    /// as opposed to the path simply not being a repository.
+    pub error: Option<String>,
 }
 
-#[tauri::command]
-pub fn get_git_status(project_path: String) -> GitStatus {
-    let Ok(repo) = git2::Repository::open(&project_path) else {
-        return GitStatus {
+impl GitStatus {
+    fn not_a_repo(error: Option<String>) -> Self {
+        GitStatus {
             is_git_repo: false,
             branch: None,
             has_upstream: false,
@@ -26,7 +38,18 @@ pub fn get_git_status(project_path: String) -> GitStatus {
             staged_files: 0,
             last_commit: None,
             local_branches: vec![],
-        };
+            branches: vec![],
+            error,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_git_status(project_path: String) -> GitStatus {
+    let repo = match git2::Repository::open(&project_path) {
+        Ok(repo) => repo,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return GitStatus::not_a_repo(None),
+        Err(e) => return GitStatus::not_a_repo(Some(e.message().to_string())),
     };
 
     let branch = repo.head().ok()
@@ -39,28 +62,32 @@ pub fn get_git_status(project_path: String) -> GitStatus {
     // Count changed + staged files via status
     let mut changed_files = 0usize;
     let mut staged_files = 0usize;
-    if let Ok(statuses) = repo.statuses(None) {
-        for entry in statuses.iter() {
-            let s = entry.status();
-            if s.intersects(
-                git2::Status::INDEX_NEW
-                    | git2::Status::INDEX_MODIFIED
-                    | git2::Status::INDEX_DELETED
-                    | git2::Status::INDEX_RENAMED
-                    | git2::Status::INDEX_TYPECHANGE,
-            ) {
-                staged_files += 1;
-            }
-            if s.intersects(
-                git2::Status::WT_MODIFIED
-                    | git2::Status::WT_DELETED
-                    | git2::Status::WT_NEW
-                    | git2::Status::WT_RENAMED
-                    | git2::Status::WT_TYPECHANGE,
-            ) {
-                changed_files += 1;
+    let mut error = None;
+    match repo.statuses(None) {
+        Ok(statuses) => {
+            for entry in statuses.iter() {
+                let s = entry.status();
+                if s.intersects(
+                    git2::Status::INDEX_NEW
+                        | git2::Status::INDEX_MODIFIED
+                        | git2::Status::INDEX_DELETED
+                        | git2::Status::INDEX_RENAMED
+                        | git2::Status::INDEX_TYPECHANGE,
+                ) {
+                    staged_files += 1;
+                }
+                if s.intersects(
+                    git2::Status::WT_MODIFIED
+                        | git2::Status::WT_DELETED
+                        | git2::Status::WT_NEW
+                        | git2::Status::WT_RENAMED
+                        | git2::Status::WT_TYPECHANGE,
+                ) {
+                    changed_files += 1;
+                }
             }
         }
+        Err(e) => error = Some(e.message().to_string()),
     }
 
     // Ahead / behind
@@ -76,15 +103,22 @@ pub fn get_git_status(project_path: String) -> GitStatus {
         .unwrap_or((0, 0, false));
 
     // Local branches
-    let local_branches: Vec<String> = repo
+    let branches: Vec<BranchInfo> = repo
         .branches(Some(git2::BranchType::Local))
         .map(|branches| {
             branches
                 .filter_map(|b| b.ok())
-                .filter_map(|(b, _)| b.name().ok().flatten().map(str::to_string))
+                .filter_map(|(b, _)| {
+                    Some(BranchInfo {
+                        name: b.name().ok().flatten()?.to_string(),
+                        is_current: b.is_head(),
+                        has_upstream: b.upstream().is_ok(),
+                    })
+                })
                 .collect()
         })
         .unwrap_or_default();
+    let local_branches = branches.iter().map(|b| b.name.clone()).collect();
 
     GitStatus {
         is_git_repo: true,
@@ -96,6 +130,8 @@ pub fn get_git_status(project_path: String) -> GitStatus {
         staged_files,
         last_commit,
         local_branches,
+        branches,
+        error,
     }
 }
 
@@ -106,9 +142,26 @@ pub fn git_init(project_path: String) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub fn git_checkout(project_path: String, branch: String) -> Result<(), String> {
-    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+/// Result of switching branches, so the UI can confirm "Switched to X (was Y)".
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BranchSwitch {
+    pub branch: String,
+    /// Branch name, or short commit id when HEAD was detached. `None` before the first commit.
+    pub previous: Option<String>,
+    pub created: bool,
+}
+
+fn head_label(repo: &git2::Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if head.is_branch() {
+        head.shorthand().map(str::to_string)
+    } else {
+        let id = head.target()?.to_string();
+        Some(id[..7.min(id.len())].to_string())
+    }
+}
+
+fn switch_to_branch(repo: &git2::Repository, branch: &str) -> Result<(), String> {
     let obj = repo
         .revparse_single(&format!("refs/heads/{}", branch))
         .map_err(|e| e.to_string())?;
@@ -118,48 +171,1080 @@ pub fn git_checkout(project_path: String, branch: String) -> Result<(), String>
 }
 
 #[tauri::command]
-pub fn git_create_branch(project_path: String, branch: String) -> Result<(), String> {
+pub fn git_checkout(project_path: String, branch: String) -> Result<BranchSwitch, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    let previous = head_label(&repo);
+    switch_to_branch(&repo, &branch)?;
+    Ok(BranchSwitch { branch, previous, created: false })
+}
+
+#[tauri::command]
+pub fn git_create_branch(project_path: String, branch: String) -> Result<BranchSwitch, String> {
     let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    let previous = head_label(&repo);
     let head = repo.head().map_err(|e| e.to_string())?;
     let commit = head.peel_to_commit().map_err(|e| e.to_string())?;
     repo.branch(&branch, &commit, false).map_err(|e| e.to_string())?;
     // Checkout the new branch
-    let obj = repo
-        .revparse_single(&format!("refs/heads/{}", branch))
-        .map_err(|e| e.to_string())?;
-    repo.checkout_tree(&obj, None).map_err(|e| e.to_string())?;
-    repo.set_head(&format!("refs/heads/{}", branch))
+    switch_to_branch(&repo, &branch)?;
+    Ok(BranchSwitch { branch, previous, created: true })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Diff HEAD against the working tree (index included, untracked files counted as added).
+/// Before the first commit there is no HEAD tree, so everything is diffed against empty.
+fn head_to_workdir_diff(repo: &git2::Repository) -> Result<git2::Diff<'_>, git2::Error> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let mut opts = git2::DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+}
+
+fn diff_stats(diff: &git2::Diff<'_>) -> Result<DiffStats, git2::Error> {
+    let stats = diff.stats()?;
+    Ok(DiffStats {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+#[tauri::command]
+pub fn git_diff_stats(project_path: String) -> Result<DiffStats, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    head_to_workdir_diff(&repo)
+        .and_then(|d| diff_stats(&d))
         .map_err(|e| e.to_string())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewFile {
+    pub path: String,
+    /// `added`, `modified`, `deleted`, `renamed` or `typechange`.
+    pub status: String,
+}
+
+/// What `git_commit_all` would commit right now.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitPreview {
+    pub files: Vec<PreviewFile>,
+    pub stats: DiffStats,
+}
+
+/// Files `git add -A` would stage and the resulting diff stats, without touching the index.
+#[tauri::command]
+pub fn git_commit_preview(project_path: String) -> Result<CommitPreview, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    let diff = head_to_workdir_diff(&repo).map_err(|e| e.to_string())?;
+    let files = diff
+        .deltas()
+        .map(|delta| {
+            let status = match delta.status() {
+                git2::Delta::Added | git2::Delta::Untracked => "added",
+                git2::Delta::Deleted => "deleted",
+                git2::Delta::Renamed => "renamed",
+                git2::Delta::Typechange => "typechange",
+                _ => "modified",
+            };
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            PreviewFile { path, status: status.to_string() }
+        })
+        .collect();
+    let stats = diff_stats(&diff).map_err(|e| e.to_string())?;
+    Ok(CommitPreview { files, stats })
+}
+
+/// Default cap on the patch returned by `git_staged_diff`.
+const STAGED_DIFF_MAX_BYTES: usize = 512 * 1024;
+
+/// The staged changes as one unified patch.
+#[derive(Debug, Serialize)]
+pub struct StagedDiff {
+    pub patch: String,
+    /// The patch was cut off at the size cap; `stats` still cover everything staged.
+    pub truncated: bool,
+    /// Files whose content is binary; the patch only has a "Binary files ... differ" line.
+    pub binary_files: Vec<String>,
+    pub stats: DiffStats,
+}
+
+/// Diff HEAD (or an empty tree before the first commit) against the index, with the patch
+/// cut off at whole lines once it reaches `max_bytes`.
+fn staged_diff(repo: &git2::Repository, max_bytes: usize) -> Result<StagedDiff, git2::Error> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+    let mut patch = String::new();
+    let mut truncated = false;
+    let mut binary_files = Vec::new();
+    let printed = diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        let mut text = String::new();
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            text.push(line.origin());
+        }
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        if line.origin() == 'B' {
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                binary_files.push(path.to_string_lossy().into_owned());
+            }
+        }
+        if patch.len() + text.len() > max_bytes {
+            truncated = true;
+            return false;
+        }
+        patch.push_str(&text);
+        true
+    });
+    if !truncated {
+        printed?;
+    }
+    Ok(StagedDiff { patch, truncated, binary_files, stats: diff_stats(&diff)? })
+}
+
+/// Everything staged, for review before committing. `max_bytes` defaults to 512 KiB.
+#[tauri::command]
+pub fn git_staged_diff(project_path: String, max_bytes: Option<usize>) -> Result<StagedDiff, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    staged_diff(&repo, max_bytes.unwrap_or(STAGED_DIFF_MAX_BYTES)).map_err(|e| e.to_string())
+}
+
+/// Default caps for `git_file_tree`.
+const FILE_TREE_MAX_DEPTH: usize = 8;
+const FILE_TREE_MAX_ENTRIES: usize = 5_000;
+
+/// A file or directory of the working tree with its git status.
+#[derive(Debug, Serialize)]
+pub struct FileTreeNode {
+    pub name: String,
+    /// Relative to the project root, `/`-separated.
+    pub path: String,
+    pub is_dir: bool,
+    /// Files: `conflicted`, `new`, `modified` (unstaged changes win over staged ones),
+    /// `staged` or `clean`. Directories: `modified` if anything below them is not clean,
+    /// even past the depth cap.
+    pub status: &'static str,
+    pub children: Vec<FileTreeNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileTree {
+    pub entries: Vec<FileTreeNode>,
+    /// Some directories were not listed because of the depth or entry cap.
+    pub truncated: bool,
+}
+
+fn file_status(s: git2::Status) -> &'static str {
+    if s.is_conflicted() {
+        "conflicted"
+    } else if s.is_wt_new() {
+        "new"
+    } else if s.intersects(
+        git2::Status::WT_MODIFIED
+            | git2::Status::WT_DELETED
+            | git2::Status::WT_RENAMED
+            | git2::Status::WT_TYPECHANGE,
+    ) {
+        "modified"
+    } else if s.intersects(
+        git2::Status::INDEX_NEW
+            | git2::Status::INDEX_MODIFIED
+            | git2::Status::INDEX_DELETED
+            | git2::Status::INDEX_RENAMED
+            | git2::Status::INDEX_TYPECHANGE,
+    ) {
+        "staged"
+    } else {
+        "clean"
+    }
+}
+
+struct TreeWalk<'a> {
+    repo: &'a git2::Repository,
+    root: &'a std::path::Path,
+    files: std::collections::HashMap<String, &'static str>,
+    dirty_dirs: std::collections::HashSet<String>,
+    max_depth: usize,
+    entries_left: usize,
+    truncated: bool,
+}
+
+impl TreeWalk<'_> {
+    /// List `rel` (empty for the root), directories first, skipping `.git` and ignored paths.
+    fn list(&mut self, rel: &str, depth: usize) -> Vec<FileTreeNode> {
+        let Ok(read) = std::fs::read_dir(self.root.join(rel)) else { return Vec::new() };
+        let mut entries: Vec<(bool, String)> = read
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let file_type = e.file_type().ok()?;
+                let name = e.file_name().to_string_lossy().into_owned();
+                (name != ".git").then_some((file_type.is_dir(), name))
+            })
+            .collect();
+        entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut nodes = Vec::new();
+        for (is_dir, name) in entries {
+            let path = if rel.is_empty() { name.clone() } else { format!("{}/{}", rel, name) };
+            if self.repo.is_path_ignored(std::path::Path::new(&path)).unwrap_or(false) {
+                continue;
+            }
+            if self.entries_left == 0 {
+                self.truncated = true;
+                break;
+            }
+            self.entries_left -= 1;
+            let (status, children) = if is_dir {
+                let status = if self.dirty_dirs.contains(&path) { "modified" } else { "clean" };
+                let children = if depth + 1 < self.max_depth {
+                    self.list(&path, depth + 1)
+                } else {
+                    self.truncated = true;
+                    Vec::new()
+                };
+                (status, children)
+            } else {
+                (self.files.get(&path).copied().unwrap_or("clean"), Vec::new())
+            };
+            nodes.push(FileTreeNode { name, path, is_dir, status, children });
+        }
+        nodes
+    }
+}
+
+fn file_tree(repo: &git2::Repository, max_depth: usize, max_entries: usize) -> Result<FileTree, git2::Error> {
+    let root = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("Repository has no working tree"))?;
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let mut files = std::collections::HashMap::new();
+    let mut dirty_dirs = std::collections::HashSet::new();
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let (Some(path), status) = (entry.path(), file_status(entry.status())) else { continue };
+        if status == "clean" {
+            continue;
+        }
+        let mut dir = path;
+        while let Some((parent, _)) = dir.rsplit_once('/') {
+            dirty_dirs.insert(parent.to_string());
+            dir = parent;
+        }
+        files.insert(path.to_string(), status);
+    }
+    let mut walk = TreeWalk {
+        repo,
+        root,
+        files,
+        dirty_dirs,
+        max_depth: max_depth.max(1),
+        entries_left: max_entries,
+        truncated: false,
+    };
+    let entries = walk.list("", 0);
+    Ok(FileTree { entries, truncated: walk.truncated })
+}
+
+/// The working tree as nested entries, each with its git status, for a changes-aware file
+/// explorer. Ignored paths are left out. Defaults: 8 levels deep, 5000 entries.
+#[tauri::command]
+pub fn git_file_tree(
+    project_path: String,
+    max_depth: Option<usize>,
+    max_entries: Option<usize>,
+) -> Result<FileTree, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    file_tree(
+        &repo,
+        max_depth.unwrap_or(FILE_TREE_MAX_DEPTH),
+        max_entries.unwrap_or(FILE_TREE_MAX_ENTRIES),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    /// Parsed from git's "WIP on <branch>:" / "On <branch>:" prefix; `None` if absent.
+    pub branch: Option<String>,
+}
+
+fn stash_branch(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    rest.split_once(':').map(|(branch, _)| branch.to_string())
+}
+
+#[tauri::command]
+pub fn git_stash_list(project_path: String) -> Result<Vec<StashEntry>, String> {
+    let mut repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    repo.stash_foreach(|index, message, _oid| {
+        entries.push(StashEntry {
+            index,
+            message: message.to_string(),
+            branch: stash_branch(message),
+        });
+        true
+    })
+    .map_err(|e| e.to_string())?;
+    Ok(entries)
+}
+
+/// Apply stash `index`, keeping it in the stash list unless `pop` is set.
+#[tauri::command]
+pub fn git_stash_apply(project_path: String, index: usize, pop: Option<bool>) -> Result<(), String> {
+    let mut repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    if pop.unwrap_or(false) {
+        repo.stash_pop(index, None)
+    } else {
+        repo.stash_apply(index, None)
+    }
+    .map_err(|e| e.to_string())
+}
+
 /// Run a git network command (pull/push) via subprocess since git2 network support
 /// requires libssh2/openssl which may not be available in the Tauri bundle.
+/// stdin is closed so hooks expecting an interactive TTY fail instead of hanging. On failure
+/// both streams are returned, since hooks often report their reason on stdout.
 fn run_git(project_path: &str, args: &[&str]) -> Result<String, String> {
     let output = std::process::Command::new("git")
         .args(args)
         .current_dir(project_path)
+        .stdin(std::process::Stdio::null())
         .output()
         .map_err(|e| e.to_string())?;
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err([stdout.trim(), stderr.trim()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n"))
     }
 }
 
+/// One line of output from a running git network command, emitted as `git-progress`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GitProgress {
+    pub operation_id: String,
+    pub line: String,
+}
+
+/// Like `run_git`, but hands each stdout/stderr line to `on_line` as it arrives. Git redraws
+/// its progress counters with `\r`, so those count as line breaks too.
+fn run_git_streaming(
+    project_path: &str,
+    args: &[&str],
+    on_line: impl Fn(&str) + Send + Sync,
+) -> Result<String, String> {
+    use std::io::BufRead;
+
+    let mut child = std::process::Command::new("git")
+        .args(args)
+        .current_dir(project_path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let forward = |stream: Box<dyn std::io::Read + Send>| {
+        let mut collected = String::new();
+        for chunk in std::io::BufReader::new(stream).split(b'\n') {
+            let Ok(chunk) = chunk else { break };
+            let chunk = String::from_utf8_lossy(&chunk);
+            for line in chunk.split('\r').filter(|l| !l.trim().is_empty()) {
+                on_line(line);
+            }
+            // Keep only the final redraw of a progress line.
+            let last = chunk.rsplit('\r').find(|l| !l.trim().is_empty()).unwrap_or("");
+            collected.push_str(last);
+            collected.push('\n');
+        }
+        collected
+    };
+    let (out, err) = std::thread::scope(|scope| {
+        let out = scope.spawn(|| forward(Box::new(stdout)));
+        let err = forward(Box::new(stderr));
+        (out.join().unwrap_or_default(), err)
+    });
+    let status = child.wait().map_err(|e| e.to_string())?;
+    // Network commands report results (e.g. a "create a pull request" URL) on stderr.
+    let combined = [out.trim(), err.trim()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if status.success() { Ok(combined) } else { Err(combined) }
+}
+
+/// Run a network command off the main thread, streaming its output as `git-progress`
+/// events tagged with `operation_id` (a fresh one if the caller didn't pick one).
+async fn run_git_with_progress(
+    app: tauri::AppHandle,
+    project_path: String,
+    args: Vec<String>,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    use tauri::Emitter;
+
+    let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_git_streaming(&project_path, &args, |line| {
+            let _ = app.emit(
+                "git-progress",
+                GitProgress { operation_id: operation_id.clone(), line: line.to_string() },
+            );
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
-pub fn git_pull(project_path: String) -> Result<String, String> {
-    run_git(&project_path, &["pull"])
+pub async fn git_pull(
+    project_path: String,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let args = vec!["pull".to_string(), "--progress".to_string()];
+    run_git_with_progress(app, project_path, args, operation_id).await
+}
+
+/// Arguments for `git push`. A branch without a remote, or `set_upstream`, pushes to
+/// `origin`; `set_upstream` without a branch pushes the current one (`HEAD`).
+fn push_args(
+    remote: Option<&str>,
+    branch: Option<&str>,
+    set_upstream: bool,
+) -> Result<Vec<String>, String> {
+    for value in [remote, branch].into_iter().flatten() {
+        if value.is_empty() || value.starts_with('-') {
+            return Err(format!("Invalid remote or branch: {:?}", value));
+        }
+    }
+    let mut args = vec!["push".to_string(), "--progress".to_string()];
+    if set_upstream {
+        args.push("-u".to_string());
+    }
+    if remote.is_some() || branch.is_some() || set_upstream {
+        args.push(remote.unwrap_or("origin").to_string());
+    }
+    match (branch, set_upstream) {
+        (Some(branch), _) => args.push(branch.to_string()),
+        (None, true) => args.push("HEAD".to_string()),
+        (None, false) => {}
+    }
+    Ok(args)
 }
 
+/// Push, returning git's output. With no arguments this is a bare `git push`.
 #[tauri::command]
-pub fn git_push(project_path: String) -> Result<String, String> {
-    run_git(&project_path, &["push"])
+pub async fn git_push(
+    project_path: String,
+    operation_id: Option<String>,
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let args = push_args(remote.as_deref(), branch.as_deref(), set_upstream.unwrap_or(false))?;
+    run_git_with_progress(app, project_path, args, operation_id).await
 }
 
+/// `--` and the pathspecs selecting `include` minus `exclude`; empty when neither is given.
+fn commit_pathspecs(include: &[String], exclude: &[String]) -> Vec<String> {
+    if include.is_empty() && exclude.is_empty() {
+        return Vec::new();
+    }
+    let mut args = vec!["--".to_string()];
+    if include.is_empty() {
+        args.push(":/".to_string()); // whole tree, like a bare `add -A`
+    } else {
+        args.extend(include.iter().cloned());
+    }
+    args.extend(exclude.iter().map(|p| format!(":(exclude){}", p)));
+    args
+}
+
+/// Stage and commit. `include` limits the commit to those paths and `exclude` leaves paths
+/// out (e.g. scratch files an agent created), even ones that were already staged; those stay
+/// staged for a later commit. With neither, everything is staged and committed.
 #[tauri::command]
-pub fn git_commit_all(project_path: String, message: String) -> Result<(), String> {
-    run_git(&project_path, &["add", "-A"])?;
-    run_git(&project_path, &["commit", "-m", &message])?;
+pub fn git_commit_all(
+    project_path: String,
+    message: String,
+    no_verify: Option<bool>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<(), String> {
+    let pathspecs = commit_pathspecs(&include.unwrap_or_default(), &exclude.unwrap_or_default());
+    let mut add = vec!["add", "-A"];
+    add.extend(pathspecs.iter().map(String::as_str));
+    run_git(&project_path, &add)?;
+    let mut args = vec!["commit", "-m", &message];
+    if no_verify.unwrap_or(false) {
+        args.push("--no-verify");
+    }
+    args.extend(pathspecs.iter().map(String::as_str));
+    run_git(&project_path, &args)?;
     Ok(())
 }
+
+/// Fill in `{branch}`, `{date}` (YYYY-MM-DD) and `{project}`; anything else is left as is.
+pub fn expand_commit_template(template: &str, branch: &str, date: &str, project: &str) -> String {
+    template
+        .replace("{branch}", branch)
+        .replace("{date}", date)
+        .replace("{project}", project)
+}
+
+/// The commit template for the project (its own, else the global one) with placeholders
+/// expanded, for seeding the commit message box. `None` when no template is set.
+#[tauri::command]
+pub async fn get_commit_template(
+    project_id: String,
+    project_path: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Option<String>, String> {
+    use crate::settings::{get_setting_db, COMMIT_TEMPLATE};
+
+    let pool = state.db();
+    let project_key = format!("{}:{}", COMMIT_TEMPLATE, project_id);
+    let template = match get_setting_db(&pool, &project_key).await.map_err(|e| e.to_string())? {
+        Some(template) => template,
+        None => match get_setting_db(&pool, COMMIT_TEMPLATE).await.map_err(|e| e.to_string())? {
+            Some(template) => template,
+            None => return Ok(None),
+        },
+    };
+    let branch = git2::Repository::open(&project_path)
+        .ok()
+        .and_then(|repo| repo.head().ok()?.shorthand().map(str::to_string))
+        .unwrap_or_default();
+    let project = crate::projects::get_project_db(&pool, &project_id)
+        .await
+        .map(|p| p.name)
+        .unwrap_or_default();
+    let (date,): (String,) = sqlx::query_as("SELECT date('now', 'localtime')")
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Some(expand_commit_template(&template, &branch, &date, &project)))
+}
+
+/// Paths with unresolved merge conflicts in the index.
+fn conflicted_files(repo: &git2::Repository) -> Result<Vec<String>, git2::Error> {
+    let mut paths: Vec<String> = repo
+        .index()?
+        .conflicts()?
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebaseOutcome {
+    /// False while the rebase is stopped on `conflicts`; resolve them, then call
+    /// `git_rebase_continue` or `git_rebase_abort`.
+    pub completed: bool,
+    pub conflicts: Vec<String>,
+    pub output: String,
+}
+
+/// Run a rebase step. A failure that leaves conflicts is an outcome, not an error.
+fn rebase_step(project_path: &str, args: &[&str]) -> Result<RebaseOutcome, String> {
+    match run_git(project_path, args) {
+        Ok(output) => Ok(RebaseOutcome { completed: true, conflicts: Vec::new(), output }),
+        Err(output) => {
+            let repo = git2::Repository::open(project_path).map_err(|e| e.to_string())?;
+            let conflicts = conflicted_files(&repo).map_err(|e| e.to_string())?;
+            if conflicts.is_empty() {
+                return Err(output);
+            }
+            Ok(RebaseOutcome { completed: false, conflicts, output })
+        }
+    }
+}
+
+/// Rebase the current branch onto `target`. On conflicts the rebase is left in progress.
+#[tauri::command]
+pub fn git_rebase(project_path: String, target: String) -> Result<RebaseOutcome, String> {
+    if target.is_empty() || target.starts_with('-') {
+        return Err(format!("Invalid rebase target: {:?}", target));
+    }
+    rebase_step(&project_path, &["rebase", &target])
+}
+
+/// Stage the resolved conflict files and continue. Files still containing conflict markers
+/// are reported back instead, without continuing.
+#[tauri::command]
+pub fn git_rebase_continue(project_path: String) -> Result<RebaseOutcome, String> {
+    let repo = git2::Repository::open(&project_path).map_err(|e| e.to_string())?;
+    let workdir = repo.workdir().ok_or("Repository has no working directory")?.to_path_buf();
+    let (unresolved, resolved): (Vec<String>, Vec<String>) = conflicted_files(&repo)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .partition(|path| {
+            std::fs::read_to_string(workdir.join(path))
+                .is_ok_and(|content| content.lines().any(|l| l.starts_with("<<<<<<<")))
+        });
+    if !unresolved.is_empty() {
+        return Ok(RebaseOutcome {
+            completed: false,
+            conflicts: unresolved,
+            output: "Conflict markers remain in these files".to_string(),
+        });
+    }
+    if !resolved.is_empty() {
+        let mut add = vec!["add", "-A", "--"];
+        add.extend(resolved.iter().map(String::as_str));
+        run_git(&project_path, &add)?;
+    }
+    // No editor: keep each commit's message as is.
+    rebase_step(&project_path, &["-c", "core.editor=true", "rebase", "--continue"])
+}
+
+#[tauri::command]
+pub fn git_rebase_abort(project_path: String) -> Result<String, String> {
+    run_git(&project_path, &["rebase", "--abort"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn commit_file(repo: &git2::Repository, name: &str, content: &str) {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents).unwrap();
+    }
+
+    #[test]
+    fn test_streaming_git_reports_lines_and_status() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a");
+        let path = dir.path().to_str().unwrap();
+
+        let lines = std::sync::Mutex::new(Vec::new());
+        let out = run_git_streaming(path, &["log", "--format=%s"], |l| {
+            lines.lock().unwrap().push(l.to_string())
+        })
+        .unwrap();
+        assert_eq!(out.trim(), lines.lock().unwrap().join("\n"));
+        assert!(!lines.lock().unwrap().is_empty());
+
+        let err = run_git_streaming(path, &["checkout", "no-such-branch"], |_| {}).unwrap_err();
+        assert!(err.contains("no-such-branch"));
+    }
+
+    #[test]
+    fn test_file_tree_overlays_status() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        std::fs::create_dir_all(dir.path().join("src/deep")).unwrap();
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        std::fs::write(dir.path().join("build/out.o"), "").unwrap();
+        commit_file(&repo, ".gitignore", "build/\n");
+        commit_file(&repo, "src/lib.rs", "a");
+        commit_file(&repo, "src/deep/x.rs", "x");
+        commit_file(&repo, "clean.txt", "c");
+        std::fs::write(dir.path().join("src/lib.rs"), "changed").unwrap();
+        std::fs::write(dir.path().join("src/deep/new.rs"), "").unwrap();
+        std::fs::write(dir.path().join("staged.txt"), "s").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        let tree = file_tree(&repo, 8, 100).unwrap();
+        assert!(!tree.truncated);
+        let top: Vec<(&str, &str)> = tree.entries.iter().map(|n| (n.path.as_str(), n.status)).collect();
+        assert_eq!(
+            top,
+            [("src", "modified"), (".gitignore", "clean"), ("clean.txt", "clean"), ("staged.txt", "staged")]
+        );
+        let src = &tree.entries[0];
+        let src_children: Vec<(&str, &str)> = src.children.iter().map(|n| (n.path.as_str(), n.status)).collect();
+        assert_eq!(src_children, [("src/deep", "modified"), ("src/lib.rs", "modified")]);
+        let deep: Vec<(&str, &str)> = src.children[0].children.iter().map(|n| (n.name.as_str(), n.status)).collect();
+        assert_eq!(deep, [("new.rs", "new"), ("x.rs", "clean")]);
+
+        // Past the depth cap a directory still reports changes below it.
+        let shallow = file_tree(&repo, 1, 100).unwrap();
+        assert!(shallow.truncated);
+        assert_eq!(shallow.entries[0].status, "modified");
+        assert!(shallow.entries[0].children.is_empty());
+
+        let capped = file_tree(&repo, 8, 2).unwrap();
+        assert!(capped.truncated);
+        assert_eq!(capped.entries.len(), 1);
+        assert_eq!(capped.entries[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_push_args() {
+        assert_eq!(push_args(None, None, false).unwrap(), ["push", "--progress"]);
+        assert_eq!(
+            push_args(None, Some("feature"), true).unwrap(),
+            ["push", "--progress", "-u", "origin", "feature"]
+        );
+        assert_eq!(
+            push_args(None, None, true).unwrap(),
+            ["push", "--progress", "-u", "origin", "HEAD"]
+        );
+        assert_eq!(
+            push_args(Some("fork"), Some("main"), false).unwrap(),
+            ["push", "--progress", "fork", "main"]
+        );
+        assert!(push_args(None, Some("--force"), false).is_err());
+    }
+
+    #[test]
+    fn test_push_sets_upstream_for_new_branch() {
+        let remote_dir = tempdir().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a");
+        repo.remote("origin", remote_dir.path().to_str().unwrap()).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let args = push_args(None, None, true).unwrap();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let out = run_git_streaming(path, &args, |_| {}).unwrap();
+        assert!(!out.is_empty(), "push output should include git's stderr report");
+
+        let head = repo.head().unwrap();
+        let branch = git2::Branch::wrap(head);
+        assert!(branch.upstream().is_ok());
+    }
+
+    #[test]
+    fn test_branch_switch_reports_previous_head() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "base");
+        let initial = head_label(&repo).unwrap();
+
+        let created = git_create_branch(path.clone(), "feature".to_string()).unwrap();
+        assert_eq!(
+            created,
+            BranchSwitch { branch: "feature".to_string(), previous: Some(initial.clone()), created: true }
+        );
+
+        let back = git_checkout(path.clone(), initial.clone()).unwrap();
+        assert_eq!(back.previous.as_deref(), Some("feature"));
+        assert!(!back.created);
+
+        repo.set_head_detached(repo.head().unwrap().target().unwrap()).unwrap();
+        let from_detached = git_checkout(path, "feature".to_string()).unwrap();
+        assert_eq!(from_detached.previous.map(|p| p.len()), Some(7));
+    }
+
+    #[test]
+    fn test_stash_list_and_apply() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let mut repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "base");
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        for content in ["first", "second"] {
+            std::fs::write(dir.path().join("a.txt"), content).unwrap();
+            repo.stash_save(&sig, &format!("wip {}", content), None).unwrap();
+        }
+
+        let stashes = git_stash_list(path.clone()).unwrap();
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(stashes[1].index, 1);
+        assert!(stashes[1].message.ends_with("wip first"));
+        let head = repo.head().unwrap().shorthand().unwrap().to_string();
+        assert_eq!(stashes[0].branch.as_deref(), Some(head.as_str()));
+
+        git_stash_apply(path.clone(), 1, None).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "first");
+        assert_eq!(git_stash_list(path.clone()).unwrap().len(), 2);
+
+        std::fs::write(dir.path().join("a.txt"), "base").unwrap();
+        git_stash_apply(path.clone(), 0, Some(true)).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "second");
+        assert_eq!(git_stash_list(path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_commit_preview_lists_files_without_staging() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "one\n");
+        commit_file(&repo, "gone.txt", "bye\n");
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::remove_file(dir.path().join("gone.txt")).unwrap();
+        std::fs::write(dir.path().join("new.txt"), "hi\n").unwrap();
+
+        let preview = git_commit_preview(dir.path().to_str().unwrap().to_string()).unwrap();
+        let mut files: Vec<(String, String)> =
+            preview.files.into_iter().map(|f| (f.path, f.status)).collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                ("a.txt".to_string(), "modified".to_string()),
+                ("gone.txt".to_string(), "deleted".to_string()),
+                ("new.txt".to_string(), "added".to_string()),
+            ]
+        );
+        assert_eq!(preview.stats.files_changed, 3);
+        assert!(repo.index().unwrap().get_path(std::path::Path::new("new.txt"), 0).is_none());
+    }
+
+    #[test]
+    fn test_rebase_stops_on_conflicts_and_continues() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        commit_file(&repo, "a.txt", "base\n");
+        let base = head_label(&repo).unwrap();
+        // The CLI, not git2, from here on: the git2 handle's cached index would go stale.
+        let commit = |name: &str, content: &str| {
+            std::fs::write(dir.path().join(name), content).unwrap();
+            run_git(&path, &["add", name]).unwrap();
+            run_git(&path, &["commit", "-q", "-m", name]).unwrap();
+        };
+
+        run_git(&path, &["checkout", "-q", "-b", "feature"]).unwrap();
+        commit("a.txt", "feature\n");
+        commit("b.txt", "feature only\n");
+        run_git(&path, &["checkout", "-q", &base]).unwrap();
+        commit("a.txt", "main\n");
+        run_git(&path, &["checkout", "-q", "feature"]).unwrap();
+
+        let outcome = git_rebase(path.clone(), base.clone()).unwrap();
+        assert!(!outcome.completed);
+        assert_eq!(outcome.conflicts, ["a.txt"]);
+
+        let still = git_rebase_continue(path.clone()).unwrap();
+        assert!(!still.completed);
+        assert_eq!(still.conflicts, ["a.txt"]);
+
+        std::fs::write(dir.path().join("a.txt"), "resolved\n").unwrap();
+        let done = git_rebase_continue(path.clone()).unwrap();
+        assert!(done.completed, "{}", done.output);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "resolved\n");
+        assert!(dir.path().join("b.txt").exists());
+        assert!(git_rebase_abort(path).is_err());
+    }
+
+    #[test]
+    fn test_rebase_rejects_option_as_target() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a");
+
+        let err = git_rebase(path, "--exec=touch pwned".to_string()).unwrap_err();
+        assert!(err.contains("Invalid rebase target"));
+        assert!(!dir.path().join("pwned").exists());
+    }
+
+    #[test]
+    fn test_commit_all_respects_include_and_exclude() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        for name in ["keep.txt", "scratch.txt", "other.txt"] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+        let path = dir.path().to_string_lossy().to_string();
+        let committed = |repo: &git2::Repository| -> Vec<String> {
+            let tree = repo.head().unwrap().peel_to_tree().unwrap();
+            tree.iter().map(|e| e.name().unwrap().to_string()).collect()
+        };
+
+        git_commit_all(path.clone(), "a".into(), None, None, Some(vec!["scratch.txt".into()])).unwrap();
+        assert_eq!(committed(&repo), ["keep.txt", "other.txt"]);
+
+        std::fs::write(dir.path().join("keep.txt"), "changed").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "changed").unwrap();
+        git_commit_all(path, "b".into(), None, Some(vec!["keep.txt".into()]), None).unwrap();
+        let statuses = repo.statuses(None).unwrap();
+        let mut dirty: Vec<String> = statuses.iter().filter_map(|e| e.path().map(str::to_string)).collect();
+        dirty.sort();
+        assert_eq!(dirty, ["other.txt", "scratch.txt"]);
+    }
+
+    #[test]
+    fn test_commit_all_leaves_out_excluded_files_already_staged() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        std::fs::write(dir.path().join("keep.txt"), "keep").unwrap();
+        std::fs::write(dir.path().join("scratch.txt"), "scratch").unwrap();
+        run_git(&path, &["add", "scratch.txt"]).unwrap();
+
+        git_commit_all(path.clone(), "a".into(), None, None, Some(vec!["scratch.txt".into()])).unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let committed: Vec<String> = tree.iter().filter_map(|e| e.name().map(str::to_string)).collect();
+        assert_eq!(committed, ["keep.txt"]);
+
+        std::fs::write(dir.path().join("keep.txt"), "changed").unwrap();
+        git_commit_all(path, "b".into(), None, Some(vec!["keep.txt".into()]), None).unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_name("scratch.txt").is_none());
+    }
+
+    #[test]
+    fn test_diff_stats_counts_modified_and_untracked() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "one\ntwo\n");
+        std::fs::write(dir.path().join("a.txt"), "one\nthree\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "new\n").unwrap();
+
+        let stats = git_diff_stats(dir.path().to_string_lossy().to_string()).unwrap();
+        assert_eq!(stats.files_changed, 2);
+        assert_eq!(stats.insertions, 2);
+        assert_eq!(stats.deletions, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_all_reports_hook_output_and_no_verify_bypasses() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let hook = dir.path().join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\necho 'lint failed: fix me'\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "x").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let err = git_commit_all(path.clone(), "msg".to_string(), None, None, None).unwrap_err();
+        assert!(err.contains("lint failed: fix me"));
+
+        git_commit_all(path, "msg".to_string(), Some(true), None, None).unwrap();
+        assert!(repo.head().is_ok());
+    }
+
+    #[test]
+    fn test_git_status_distinguishes_missing_from_broken_repo() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+
+        let status = get_git_status(path.clone());
+        assert!(!status.is_git_repo);
+        assert!(status.error.is_none());
+
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a");
+        let status = get_git_status(path.clone());
+        assert!(status.is_git_repo);
+        assert!(status.error.is_none());
+
+        assert_eq!(status.branches.len(), 1);
+        assert!(status.branches[0].is_current);
+        assert!(!status.branches[0].has_upstream);
+        assert_eq!(status.local_branches, [status.branches[0].name.clone()]);
+
+        std::fs::write(dir.path().join(".git/index"), b"garbage").unwrap();
+        let status = get_git_status(path);
+        assert!(status.error.is_some());
+    }
+
+    #[test]
+    fn test_staged_diff_covers_index_only() {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("blob.bin"), [0u8, 1, 2, 0, 255]).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("a.txt")).unwrap();
+        index.add_path(std::path::Path::new("blob.bin")).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.path().join("unstaged.txt"), "nope\n").unwrap();
+
+        // Before the first commit everything staged shows as added.
+        let diff = staged_diff(&repo, STAGED_DIFF_MAX_BYTES).unwrap();
+        assert!(diff.patch.contains("+one"));
+        assert!(!diff.patch.contains("unstaged"));
+        assert_eq!(diff.binary_files, ["blob.bin"]);
+        assert_eq!(diff.stats.files_changed, 2);
+        assert!(!diff.truncated);
+
+        let cut = staged_diff(&repo, 20).unwrap();
+        assert!(cut.truncated);
+        assert!(cut.patch.len() <= 20);
+        assert_eq!(cut.stats.files_changed, 2);
+    }
+
+    #[test]
+    fn test_expand_commit_template() {
+        assert_eq!(
+            expand_commit_template("[{branch}] {date}: {project} {unknown}", "feat/x", "2026-10-16", "spawn"),
+            "[feat/x] 2026-10-16: spawn {unknown}"
+        );
+        assert_eq!(expand_commit_template("{branch}{branch}", "", "d", "p"), "");
+    }
+
+    #[test]
+    fn test_diff_stats_without_head() {
+        let dir = tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "x\ny\n").unwrap();
+
+        let stats = git_diff_stats(dir.path().to_string_lossy().to_string()).unwrap();
+        assert_eq!(stats.files_changed, 1);
+        assert_eq!(stats.insertions, 2);
+    }
+}
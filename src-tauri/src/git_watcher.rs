@@ -0,0 +1,157 @@
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Builds touch thousands of files in bursts; one refresh per quiet period is plenty.
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitStatusChanged {
+    pub project_id: String,
+    pub project_path: String,
+}
+
+/// Payload of `project-path-missing`, emitted once each time a watched project's directory
+/// is moved or deleted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectPathMissing {
+    pub project_id: String,
+    pub project_path: String,
+}
+
+/// One recursive watcher per open project, emitting `git-status-changed` so the frontend
+/// can re-run `get_git_status` instead of polling. Changes also drop the project's cached
+/// file listing and git info.
+#[derive(Default)]
+pub struct GitWatcher {
+    watchers: Mutex<HashMap<String, Debouncer<RecommendedWatcher>>>,
+}
+
+impl GitWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching `project_path`, replacing any existing watcher for the project.
+    pub fn watch(&self, project_id: &str, project_path: &str, app: AppHandle) -> Result<(), String> {
+        let root = PathBuf::from(project_path);
+        let payload = GitStatusChanged {
+            project_id: project_id.to_string(),
+            project_path: project_path.to_string(),
+        };
+        let handler_root = root.clone();
+        let missing_reported = std::sync::atomic::AtomicBool::new(false);
+        let mut debouncer = new_debouncer(DEBOUNCE, move |res: DebounceEventResult| {
+            let Ok(events) = res else { return };
+            if !handler_root.is_dir() {
+                if !missing_reported.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                    let state = app.state::<crate::AppState>();
+                    state.file_index.invalidate(&payload.project_id);
+                    state.git_info.invalidate(&payload.project_path);
+                    let _ = app.emit(
+                        "project-path-missing",
+                        ProjectPathMissing {
+                            project_id: payload.project_id.clone(),
+                            project_path: payload.project_path.clone(),
+                        },
+                    );
+                }
+                return;
+            }
+            // Back again (e.g. a moved repo was restored): report the next disappearance too.
+            missing_reported.store(false, std::sync::atomic::Ordering::Relaxed);
+            if affects_status(&handler_root, events.iter().map(|e| e.path.as_path())) {
+                let state = app.state::<crate::AppState>();
+                state.file_index.invalidate(&payload.project_id);
+                state.git_info.invalidate(&payload.project_path);
+                let _ = app.emit("git-status-changed", payload.clone());
+            }
+        })
+        .map_err(|e| e.to_string())?;
+        debouncer
+            .watcher()
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+
+        self.watchers
+            .lock()
+            .unwrap()
+            .insert(project_id.to_string(), debouncer);
+        Ok(())
+    }
+
+    /// Stop watching; dropping the debouncer stops its thread. No-op if not watched.
+    pub fn unwatch(&self, project_id: &str) {
+        self.watchers.lock().unwrap().remove(project_id);
+    }
+
+    pub fn is_watching(&self, project_id: &str) -> bool {
+        self.watchers.lock().unwrap().contains_key(project_id)
+    }
+
+    pub fn unwatch_all(&self) {
+        self.watchers.lock().unwrap().clear();
+    }
+}
+
+/// Whether any changed path can affect `git status`: anything in `.git` except the object
+/// store and reflogs, or a working-tree path that isn't gitignored.
+fn affects_status<'a>(root: &Path, paths: impl IntoIterator<Item = &'a Path>) -> bool {
+    let repo = git2::Repository::open(root).ok();
+    paths.into_iter().any(|path| {
+        let Ok(rel) = path.strip_prefix(root) else { return false };
+        if let Ok(in_git) = rel.strip_prefix(".git") {
+            return !(in_git.starts_with("objects") || in_git.starts_with("logs"));
+        }
+        match &repo {
+            Some(repo) => !repo.is_path_ignored(rel).unwrap_or(false),
+            None => true,
+        }
+    })
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub fn watch_project(
+    project_id: String,
+    project_path: String,
+    app: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    state.git_watcher.watch(&project_id, &project_path, app)
+}
+
+#[tauri::command]
+pub fn unwatch_project(project_id: String, state: tauri::State<'_, crate::AppState>) {
+    state.git_watcher.unwatch(&project_id);
+    state.file_index.invalidate(&project_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_affects_status_filters_ignored_and_object_store() {
+        let dir = tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        let root = dir.path();
+
+        assert!(affects_status(root, [root.join("src/main.rs").as_path()]));
+        assert!(affects_status(root, [root.join(".git/index").as_path()]));
+        assert!(!affects_status(root, [root.join("target/debug/foo").as_path()]));
+        assert!(!affects_status(root, [root.join(".git/objects/ab/cdef").as_path()]));
+        assert!(affects_status(
+            root,
+            [root.join("target/x").as_path(), root.join("README.md").as_path()]
+        ));
+    }
+}
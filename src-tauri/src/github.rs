@@ -1,6 +1,15 @@
 use anyhow::Result;
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+pub(crate) const USER_AGENT: &str = "spawn/1.0";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GithubIssue {
@@ -9,6 +18,189 @@ pub struct GithubIssue {
     pub body: Option<String>,
     pub state: String,
     pub html_url: String,
+    /// ISO 8601 timestamps as returned by the API. Optional so older cached data still parses.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<IssueLabel>,
+    /// Whether a task in the project already references this issue number. Filled in by
+    /// `fetch_project_issues`, not by the API.
+    #[serde(default)]
+    pub already_imported: bool,
+}
+
+/// A label as the API sends it. Plain names (GitLab, and issues cached before colors were
+/// kept) parse too, without color or description.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawLabel")]
+pub struct IssueLabel {
+    pub name: String,
+    /// Hex without `#`, e.g. `"d73a4a"`.
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawLabel {
+    Name(String),
+    Object {
+        name: String,
+        #[serde(default)]
+        color: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl From<RawLabel> for IssueLabel {
+    fn from(raw: RawLabel) -> Self {
+        match raw {
+            RawLabel::Name(name) => IssueLabel { name, color: None, description: None },
+            RawLabel::Object { name, color, description } => IssueLabel { name, color, description },
+        }
+    }
+}
+
+/// How long `list_repo_labels` reuses a repository's labels. Labels rarely change, and a
+/// filter picker may be opened many times in a row.
+const LABELS_TTL: Duration = Duration::from_secs(300);
+
+/// Recently fetched repository labels, keyed by `owner/repo`.
+#[derive(Default)]
+pub struct LabelCache {
+    entries: Mutex<HashMap<String, (Instant, Vec<IssueLabel>)>>,
+}
+
+impl LabelCache {
+    pub fn get(&self, key: &str) -> Option<Vec<IssueLabel>> {
+        let entries = self.entries.lock().unwrap();
+        let (fetched_at, labels) = entries.get(key)?;
+        (fetched_at.elapsed() < LABELS_TTL).then(|| labels.clone())
+    }
+
+    pub fn insert(&self, key: &str, labels: Vec<IssueLabel>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (Instant::now(), labels));
+    }
+}
+
+/// Issues plus the id of the fetch that produced them, so the frontend can drop
+/// responses from a fetch that has since been superseded.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueFetch {
+    pub request_id: u64,
+    pub issues: Vec<GithubIssue>,
+}
+
+/// Payload of `github-issues-page`, emitted per page while `fetch_project_issues` runs and
+/// once more with `complete` set when it ends (with `error` if it failed or was cancelled).
+/// Events from a superseded fetch carry an older `request_id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuePage {
+    pub project_id: String,
+    pub request_id: u64,
+    pub issues: Vec<GithubIssue>,
+    pub complete: bool,
+    pub error: Option<String>,
+}
+
+/// In-flight issue fetches, one per project. Starting a fetch drops the previous fetch's
+/// cancel sender, which aborts it.
+#[derive(Default)]
+pub struct IssueFetches {
+    next_id: AtomicU64,
+    in_flight: Mutex<HashMap<String, (u64, oneshot::Sender<()>)>>,
+}
+
+impl IssueFetches {
+    /// Register a new fetch for the project, cancelling any older one. The receiver
+    /// resolves when this fetch is cancelled or superseded.
+    fn begin(&self, project_id: &str) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (tx, rx) = oneshot::channel();
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(project_id.to_string(), (id, tx));
+        (id, rx)
+    }
+
+    /// Forget a finished fetch, unless a newer one has replaced it.
+    fn finish(&self, project_id: &str, id: u64) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(project_id).is_some_and(|(current, _)| *current == id) {
+            in_flight.remove(project_id);
+        }
+    }
+
+    /// Cancel the project's in-flight fetch. Returns whether there was one.
+    pub fn cancel(&self, project_id: &str) -> bool {
+        self.in_flight.lock().unwrap().remove(project_id).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    #[default]
+    Open,
+    Closed,
+    All,
+}
+
+impl IssueState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueState::Open => "open",
+            IssueState::Closed => "closed",
+            IssueState::All => "all",
+        }
+    }
+}
+
+pub const DEFAULT_PER_PAGE: u32 = 50;
+/// The most GitHub (and GitLab) return per page.
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Page size and optional total cap for an issue fetch. Pages are followed until the cap
+/// is reached or the API runs out of issues.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IssuePaging {
+    pub per_page: u32,
+    pub limit: Option<usize>,
+}
+
+impl Default for IssuePaging {
+    fn default() -> Self {
+        Self { per_page: DEFAULT_PER_PAGE, limit: None }
+    }
+}
+
+impl IssuePaging {
+    /// Out-of-range values are clamped to the API's bounds instead of rejected.
+    pub fn new(per_page: Option<u32>, limit: Option<usize>) -> Self {
+        let limit = limit.map(|l| l.max(1));
+        let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+        // No point asking for more per page than will be kept.
+        let per_page = limit.map_or(per_page, |l| per_page.min(l as u32));
+        Self { per_page, limit }
+    }
+
+    /// How many more issues fit under `limit` once `collected` have been kept.
+    pub fn room(&self, collected: usize) -> usize {
+        self.limit.map_or(usize::MAX, |l| l.saturating_sub(collected))
+    }
+
+    /// Whether to request another page after one that returned `page_len` items,
+    /// with `collected` items kept so far.
+    pub fn wants_more(&self, page_len: usize, collected: usize) -> bool {
+        page_len == self.per_page as usize && self.limit.is_none_or(|l| collected < l)
+    }
 }
 
 pub fn parse_repo_from_url(url: &str) -> Option<(String, String)> {
@@ -31,10 +223,14 @@ pub fn parse_repo_from_url(url: &str) -> Option<(String, String)> {
     None
 }
 
+/// Keyring entry for the token used by projects that don't have their own.
+const USER_TOKEN_ENTRY: &str = "github-user";
+
+/// The project's own token, falling back to the user-level one.
 pub fn get_github_token(project_id: &str) -> Option<String> {
-    Entry::new("spawn", &format!("github-{}", project_id))
-        .ok()
-        .and_then(|e| e.get_password().ok())
+    [format!("github-{}", project_id), USER_TOKEN_ENTRY.to_string()]
+        .iter()
+        .find_map(|name| Entry::new("spawn", name).ok()?.get_password().ok())
 }
 
 pub fn set_github_token(project_id: &str, token: &str) -> Result<()> {
@@ -43,21 +239,286 @@ pub fn set_github_token(project_id: &str, token: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn fetch_issues(owner: &str, repo: &str, token: &str) -> Result<Vec<GithubIssue>> {
-    let client = reqwest::Client::new();
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/issues?state=open&per_page=50",
+/// Where a project's `origin` remote is hosted, which decides the issues API to talk to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitProvider {
+    GitHub,
+    /// gitlab.com or a self-hosted instance whose hostname contains "gitlab".
+    GitLab { host: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRepo {
+    pub provider: GitProvider,
+    /// Owner for GitHub; full namespace (possibly nested groups) for GitLab.
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RemoteRepo {
+    /// Browser URL for the repository.
+    pub fn web_url(&self) -> String {
+        match &self.provider {
+            GitProvider::GitHub => format!("https://github.com/{}/{}", self.owner, self.repo),
+            GitProvider::GitLab { host } => format!("https://{}/{}/{}", host, self.owner, self.repo),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectRemote {
+    pub url: Option<String>,
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+    pub web_url: Option<String>,
+}
+
+/// Detect the provider from a remote URL (`https://host/...` or `git@host:...`).
+pub fn parse_remote(url: &str) -> Option<RemoteRepo> {
+    if let Some((owner, repo)) = parse_repo_from_url(url) {
+        return Some(RemoteRepo { provider: GitProvider::GitHub, owner, repo });
+    }
+    let (host, path) = if let Some(rest) = url.strip_prefix("https://") {
+        rest.split_once('/')?
+    } else if let Some(rest) = url.strip_prefix("git@") {
+        rest.split_once(':')?
+    } else {
+        return None;
+    };
+    if !host.contains("gitlab") {
+        return None;
+    }
+    let clean = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, repo) = clean.rsplit_once('/')?;
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some(RemoteRepo {
+        provider: GitProvider::GitLab { host: host.to_string() },
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+    })
+}
+
+/// Shared client so connections and TLS sessions are pooled across fetches.
+/// User-Agent, API headers and timeouts are configured here once.
+pub(crate) fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::ACCEPT,
+            reqwest::header::HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(
+            "X-GitHub-Api-Version",
+            reqwest::header::HeaderValue::from_static("2022-11-28"),
+        );
+        reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .default_headers(headers)
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .expect("failed to build HTTP client")
+    })
+}
+
+/// Start an authenticated GitHub API request. All GitHub calls go through this.
+fn github_request(method: reqwest::Method, url: &str, token: &str) -> reqwest::RequestBuilder {
+    http_client().request(method, url).bearer_auth(token)
+}
+
+fn map_request_error(e: reqwest::Error) -> anyhow::Error {
+    if e.is_timeout() {
+        anyhow::anyhow!("GitHub request timed out")
+    } else {
+        e.into()
+    }
+}
+
+pub fn issues_url(owner: &str, repo: &str, state: IssueState, per_page: u32, page: u32) -> String {
+    format!(
+        "https://api.github.com/repos/{}/{}/issues?state={}&per_page={}&page={}",
+        owner,
+        repo,
+        state.as_str(),
+        per_page,
+        page
+    )
+}
+
+pub async fn fetch_issues(
+    owner: &str,
+    repo: &str,
+    token: &str,
+    state: IssueState,
+    paging: IssuePaging,
+    mut on_page: impl FnMut(&[GithubIssue]) + Send,
+) -> Result<Vec<GithubIssue>> {
+    let mut issues = Vec::new();
+    let mut skipped = 0;
+    for page in 1.. {
+        let url = issues_url(owner, repo, state, paging.per_page, page);
+        let values: Vec<serde_json::Value> = github_request(reqwest::Method::GET, &url, token)
+            .send()
+            .await
+            .map_err(map_request_error)?
+            .json()
+            .await
+            .map_err(map_request_error)?;
+        let page_len = values.len();
+        let (mut parsed, page_skipped) = parse_issues(values);
+        parsed.truncate(paging.room(issues.len()));
+        on_page(&parsed);
+        issues.extend(parsed);
+        skipped += page_skipped;
+        if !paging.wants_more(page_len, issues.len()) {
+            break;
+        }
+    }
+    if skipped > 0 {
+        eprintln!("Skipped {} malformed issue(s) from {}/{}", skipped, owner, repo);
+    }
+    Ok(issues)
+}
+
+/// Parse each issue on its own so one unexpected shape doesn't lose the whole page.
+/// Returns the parsed issues and how many were skipped.
+fn parse_issues(values: Vec<serde_json::Value>) -> (Vec<GithubIssue>, usize) {
+    let total = values.len();
+    let issues: Vec<GithubIssue> = values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect();
+    let skipped = total - issues.len();
+    (issues, skipped)
+}
+
+/// One issue by number. A deleted or nonexistent issue is reported as such rather than as
+/// a bare HTTP error.
+pub async fn fetch_issue(owner: &str, repo: &str, token: &str, number: u64) -> Result<GithubIssue> {
+    let url = format!("https://api.github.com/repos/{}/{}/issues/{}", owner, repo, number);
+    let response = github_request(reqwest::Method::GET, &url, token)
+        .send()
+        .await
+        .map_err(map_request_error)?;
+    if matches!(response.status(), reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE) {
+        return Err(anyhow::anyhow!("Issue #{} not found in {}/{} (it may have been deleted)", number, owner, repo));
+    }
+    response
+        .error_for_status()
+        .map_err(map_request_error)?
+        .json()
+        .await
+        .map_err(map_request_error)
+}
+
+/// Search query counting the repository's open issues (pull requests excluded). One result
+/// per page, since only `total_count` is read.
+pub fn open_issue_count_url(owner: &str, repo: &str) -> String {
+    format!(
+        "https://api.github.com/search/issues?q=repo:{}/{}+state:open+type:issue&per_page=1",
         owner, repo
-    );
-    let issues: Vec<GithubIssue> = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("User-Agent", "spawn/1.0")
+    )
+}
+
+/// Whether a response means the rate limit is used up rather than a real failure.
+fn is_rate_limited(response: &reqwest::Response) -> bool {
+    let status = response.status();
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || (status == reqwest::StatusCode::FORBIDDEN
+            && response
+                .headers()
+                .get("x-ratelimit-remaining")
+                .is_some_and(|v| v.as_bytes() == b"0"))
+}
+
+/// Open issue count via the search API; `None` when rate limited. The search API has its own,
+/// lower rate limit than the rest of the API.
+pub async fn fetch_open_issue_count(owner: &str, repo: &str, token: &str) -> Result<Option<u64>> {
+    #[derive(Deserialize)]
+    struct SearchCount {
+        total_count: u64,
+    }
+    let response = github_request(reqwest::Method::GET, &open_issue_count_url(owner, repo), token)
         .send()
-        .await?
+        .await
+        .map_err(map_request_error)?;
+    if is_rate_limited(&response) {
+        return Ok(None);
+    }
+    let count: SearchCount = response
+        .error_for_status()
+        .map_err(map_request_error)?
         .json()
-        .await?;
-    Ok(issues)
+        .await
+        .map_err(map_request_error)?;
+    Ok(Some(count.total_count))
+}
+
+/// Every label defined on the repository, following pages until a short one.
+pub async fn fetch_repo_labels(owner: &str, repo: &str, token: &str) -> Result<Vec<IssueLabel>> {
+    let mut labels = Vec::new();
+    for page in 1.. {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/labels?per_page={}&page={}",
+            owner, repo, MAX_PER_PAGE, page
+        );
+        let batch: Vec<IssueLabel> = github_request(reqwest::Method::GET, &url, token)
+            .send()
+            .await
+            .map_err(map_request_error)?
+            .error_for_status()
+            .map_err(map_request_error)?
+            .json()
+            .await
+            .map_err(map_request_error)?;
+        let done = batch.len() < MAX_PER_PAGE as usize;
+        labels.extend(batch);
+        if done {
+            break;
+        }
+    }
+    Ok(labels)
+}
+
+/// PATCH the issue to `state=closed`.
+pub async fn close_issue(owner: &str, repo: &str, token: &str, number: u64) -> Result<()> {
+    let url = format!("https://api.github.com/repos/{}/{}/issues/{}", owner, repo, number);
+    github_request(reqwest::Method::PATCH, &url, token)
+        .json(&serde_json::json!({ "state": "closed" }))
+        .send()
+        .await
+        .map_err(map_request_error)?
+        .error_for_status()
+        .map_err(map_request_error)?;
+    Ok(())
+}
+
+/// The `origin` remote URL of the repository at `project_path`.
+pub fn origin_url(project_path: &str) -> Result<String, String> {
+    let repo = git2::Repository::open(project_path)
+        .map_err(|e| format!("Could not open git repo: {}", e))?;
+    let remote = repo
+        .find_remote("origin")
+        .map_err(|_| "No 'origin' remote found".to_string())?;
+    remote
+        .url()
+        .map(str::to_string)
+        .ok_or_else(|| "Remote URL is not valid UTF-8".to_string())
+}
+
+#[tauri::command]
+pub fn get_project_remote(project_path: String) -> ProjectRemote {
+    let url = origin_url(&project_path).ok();
+    let remote = url.as_deref().and_then(parse_remote);
+    ProjectRemote {
+        web_url: remote.as_ref().map(RemoteRepo::web_url),
+        owner: remote.as_ref().map(|r| r.owner.clone()),
+        repo: remote.map(|r| r.repo),
+        url,
+    }
 }
 
 #[tauri::command]
@@ -69,38 +530,270 @@ pub fn set_project_github_token(
 }
 
 #[tauri::command]
-pub async fn fetch_project_issues(
+pub fn set_user_github_token(token: String) -> Result<(), String> {
+    Entry::new("spawn", USER_TOKEN_ENTRY)
+        .and_then(|e| e.set_password(&token))
+        .map_err(|e| e.to_string())
+}
+
+/// Remove the user-level token; a no-op when none is stored.
+#[tauri::command]
+pub fn clear_user_github_token() -> Result<(), String> {
+    match Entry::new("spawn", USER_TOKEN_ENTRY).and_then(|e| e.delete_password()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+async fn fetch_issues_for_project(
+    project_id: &str,
+    project_path: &str,
+    state: IssueState,
+    paging: IssuePaging,
+    on_page: impl FnMut(&[GithubIssue]) + Send,
+) -> Result<Vec<GithubIssue>, String> {
+    // origin_url drops all non-Send git2 types before the first await point,
+    // so the future stays Send.
+    let url = origin_url(project_path)?;
+    let remote = parse_remote(&url)
+        .ok_or_else(|| format!("Could not parse GitHub/GitLab owner/repo from remote URL: {}", url))?;
+
+    match remote.provider {
+        GitProvider::GitHub => {
+            let token = get_github_token(project_id)
+                .ok_or_else(|| "No GitHub token configured for this project".to_string())?;
+            fetch_issues(&remote.owner, &remote.repo, &token, state, paging, on_page)
+                .await
+                .map_err(|e| e.to_string())
+        }
+        GitProvider::GitLab { host } => {
+            let token = crate::gitlab::get_gitlab_token(project_id)
+                .ok_or_else(|| "No GitLab token configured for this project".to_string())?;
+            let path = format!("{}/{}", remote.owner, remote.repo);
+            crate::gitlab::fetch_issues(&host, &path, &token, state, paging, on_page)
+                .await
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Close an issue in the project's GitHub repository using the project's token.
+pub async fn close_issue_for_project(
+    project_id: &str,
+    project_path: &str,
+    number: u64,
+) -> Result<(), String> {
+    let url = origin_url(project_path)?;
+    let remote = parse_remote(&url)
+        .filter(|r| r.provider == GitProvider::GitHub)
+        .ok_or_else(|| format!("Not a GitHub remote: {}", url))?;
+    let token = get_github_token(project_id)
+        .ok_or_else(|| "No GitHub token configured for this project".to_string())?;
+    close_issue(&remote.owner, &remote.repo, &token, number)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn close_github_issue(
     project_id: String,
     project_path: String,
-) -> Result<Vec<GithubIssue>, String> {
+    issue_number: u64,
+) -> Result<(), String> {
+    close_issue_for_project(&project_id, &project_path, issue_number).await
+}
+
+/// Number of open issues for a badge, without fetching them; `None` when GitHub's rate limit
+/// is exhausted, so the badge can simply be hidden.
+#[tauri::command]
+pub async fn count_open_issues(project_id: String, project_path: String) -> Result<Option<u64>, String> {
+    let url = origin_url(&project_path)?;
+    let remote = parse_remote(&url)
+        .filter(|r| r.provider == GitProvider::GitHub)
+        .ok_or_else(|| format!("Not a GitHub remote: {}", url))?;
     let token = get_github_token(&project_id)
         .ok_or_else(|| "No GitHub token configured for this project".to_string())?;
+    fetch_open_issue_count(&remote.owner, &remote.repo, &token)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Extract the remote URL synchronously, then drop all non-Send git2 types
-    // before the first await point so the future is Send.
-    let (owner, repo_name) = {
-        let repo = git2::Repository::open(&project_path)
-            .map_err(|e| format!("Could not open git repo: {}", e))?;
-        let remote = repo
-            .find_remote("origin")
-            .map_err(|_| "No 'origin' remote found".to_string())?;
-        let url = remote
-            .url()
-            .ok_or_else(|| "Remote URL is not valid UTF-8".to_string())?
-            .to_string();
-        parse_repo_from_url(&url)
-            .ok_or_else(|| format!("Could not parse GitHub owner/repo from remote URL: {}", url))?
-    };
+/// All labels of the project's GitHub repository with their colors, e.g. for a label filter.
+/// Cached for a few minutes per repository.
+#[tauri::command]
+pub async fn list_repo_labels(
+    project_id: String,
+    project_path: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<IssueLabel>, String> {
+    let url = origin_url(&project_path)?;
+    let remote = parse_remote(&url)
+        .filter(|r| r.provider == GitProvider::GitHub)
+        .ok_or_else(|| format!("Not a GitHub remote: {}", url))?;
+    let key = format!("{}/{}", remote.owner, remote.repo);
+    if let Some(labels) = state.label_cache.get(&key) {
+        return Ok(labels);
+    }
+    let token = get_github_token(&project_id)
+        .ok_or_else(|| "No GitHub token configured for this project".to_string())?;
+    let labels = fetch_repo_labels(&remote.owner, &remote.repo, &token)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.label_cache.insert(&key, labels.clone());
+    Ok(labels)
+}
 
-    fetch_issues(&owner, &repo_name, &token)
+/// A single issue of the project's GitHub repository, e.g. to refresh a task's source issue.
+#[tauri::command]
+pub async fn fetch_github_issue(
+    project_id: String,
+    project_path: String,
+    issue_number: u64,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<GithubIssue, String> {
+    let url = origin_url(&project_path)?;
+    let remote = parse_remote(&url)
+        .filter(|r| r.provider == GitProvider::GitHub)
+        .ok_or_else(|| format!("Not a GitHub remote: {}", url))?;
+    let token = get_github_token(&project_id)
+        .ok_or_else(|| "No GitHub token configured for this project".to_string())?;
+    let mut issue = fetch_issue(&remote.owner, &remote.repo, &token, issue_number)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    let imported = crate::tasks::imported_issue_numbers_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    issue.already_imported = imported.contains(&(issue.number as i64));
+    Ok(issue)
+}
+
+/// Fetch issues for a project. A newer fetch for the same project, or `cancel_project_issues`,
+/// aborts this one with an error. `per_page` defaults to 50 (max 100); `limit` caps the total
+/// across pages, otherwise every page is loaded.
+#[tauri::command]
+pub async fn fetch_project_issues(
+    project_id: String,
+    project_path: String,
+    issue_state: Option<IssueState>,
+    per_page: Option<u32>,
+    limit: Option<usize>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<IssueFetch, String> {
+    use tauri::Emitter;
+
+    let (request_id, cancelled) = state.issue_fetches.begin(&project_id);
+    let imported = crate::tasks::imported_issue_numbers_db(&state.db(), &project_id).await;
+    let imported = match imported {
+        Ok(imported) => imported,
+        Err(e) => {
+            state.issue_fetches.finish(&project_id, request_id);
+            return Err(e.to_string());
+        }
+    };
+    let mark_imported = |issues: &mut [GithubIssue]| {
+        for issue in issues {
+            issue.already_imported = imported.contains(&(issue.number as i64));
+        }
+    };
+    let emit_page = |issues: Vec<GithubIssue>, complete: bool, error: Option<String>| {
+        let page = IssuePage { project_id: project_id.clone(), request_id, issues, complete, error };
+        let _ = app.emit("github-issues-page", page);
+    };
+    let result = tokio::select! {
+        res = fetch_issues_for_project(
+            &project_id,
+            &project_path,
+            issue_state.unwrap_or_default(),
+            IssuePaging::new(per_page, limit),
+            |page| {
+                let mut page = page.to_vec();
+                mark_imported(&mut page);
+                emit_page(page, false, None);
+            },
+        ) => res,
+        _ = cancelled => Err("Issue fetch cancelled".to_string()),
+    };
+    state.issue_fetches.finish(&project_id, request_id);
+    emit_page(Vec::new(), true, result.as_ref().err().cloned());
+    let mut issues = result?;
+    mark_imported(&mut issues);
+    Ok(IssueFetch { request_id, issues })
+}
+
+#[tauri::command]
+pub fn cancel_project_issues(project_id: String, state: tauri::State<'_, crate::AppState>) -> bool {
+    state.issue_fetches.cancel(&project_id)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_newer_issue_fetch_supersedes_older() {
+        let fetches = IssueFetches::default();
+        let (first, mut first_rx) = fetches.begin("p1");
+        let (second, mut second_rx) = fetches.begin("p1");
+        assert!(second > first);
+        assert!(matches!(first_rx.try_recv(), Err(oneshot::error::TryRecvError::Closed)));
+
+        // A stale fetch finishing must not drop the newer one's registration.
+        fetches.finish("p1", first);
+        assert!(matches!(second_rx.try_recv(), Err(oneshot::error::TryRecvError::Empty)));
+
+        assert!(fetches.cancel("p1"));
+        assert!(matches!(second_rx.try_recv(), Err(oneshot::error::TryRecvError::Closed)));
+        assert!(!fetches.cancel("p1"));
+    }
+
+    #[test]
+    fn test_parse_issues_skips_malformed() {
+        let values = vec![
+            serde_json::json!({"number": 1, "title": "Ok", "body": null, "state": "open", "html_url": "u1"}),
+            serde_json::json!({"number": "two", "title": "Bad"}),
+            serde_json::json!({"number": 3, "title": "Also ok", "body": "b", "state": "closed", "html_url": "u3"}),
+        ];
+        let (issues, skipped) = parse_issues(values);
+        assert_eq!(issues.iter().map(|i| i.number).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_issue_labels_parse_from_api_and_round_trip() {
+        let issue: GithubIssue = serde_json::from_value(serde_json::json!({
+            "number": 7, "title": "T", "body": "b", "state": "open", "html_url": "u",
+            "labels": [{"id": 1, "name": "bug", "color": "f00"}, {"id": 2, "name": "p1"}]
+        }))
+        .unwrap();
+        let names: Vec<&str> = issue.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["bug", "p1"]);
+        assert_eq!(issue.labels[0].color.as_deref(), Some("f00"));
+        assert_eq!(issue.labels[1].color, None);
+
+        let again: GithubIssue = serde_json::from_value(serde_json::to_value(&issue).unwrap()).unwrap();
+        assert_eq!(again.labels, issue.labels);
+
+        // Issues cached with plain label names still load.
+        let cached: GithubIssue = serde_json::from_value(serde_json::json!({
+            "number": 7, "title": "T", "body": null, "state": "open", "html_url": "u",
+            "labels": ["bug"]
+        }))
+        .unwrap();
+        assert_eq!(cached.labels, [IssueLabel { name: "bug".into(), color: None, description: None }]);
+    }
+
+    #[test]
+    fn test_label_cache_expires() {
+        let cache = LabelCache::default();
+        assert_eq!(cache.get("o/r"), None);
+        let labels = vec![IssueLabel { name: "bug".into(), color: Some("d73a4a".into()), description: None }];
+        cache.insert("o/r", labels.clone());
+        assert_eq!(cache.get("o/r"), Some(labels));
+        cache.entries.lock().unwrap().get_mut("o/r").unwrap().0 -= LABELS_TTL;
+        assert_eq!(cache.get("o/r"), None);
+    }
+
     #[test]
     fn test_parse_https_url() {
         let url = "https://github.com/owner/repo.git";
@@ -128,6 +821,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_issue_deserializes_timestamps() {
+        let json = r#"{"number":1,"title":"t","body":null,"state":"open",
+            "html_url":"https://github.com/o/r/issues/1",
+            "created_at":"2024-01-02T03:04:05Z","updated_at":"2024-02-03T04:05:06Z"}"#;
+        let issue: GithubIssue = serde_json::from_str(json).unwrap();
+        assert_eq!(issue.created_at.as_deref(), Some("2024-01-02T03:04:05Z"));
+        assert_eq!(issue.updated_at.as_deref(), Some("2024-02-03T04:05:06Z"));
+    }
+
+    #[test]
+    fn test_issue_without_timestamps_still_deserializes() {
+        let json = r#"{"number":1,"title":"t","body":null,"state":"open","html_url":"u"}"#;
+        let issue: GithubIssue = serde_json::from_str(json).unwrap();
+        assert!(issue.created_at.is_none());
+        assert!(issue.updated_at.is_none());
+    }
+
+    #[test]
+    fn test_issue_paging_clamps_and_stops() {
+        assert_eq!(IssuePaging::new(None, None), IssuePaging::default());
+        assert_eq!(IssuePaging::new(Some(500), None).per_page, MAX_PER_PAGE);
+        assert_eq!(IssuePaging::new(Some(0), None).per_page, 1);
+        assert_eq!(IssuePaging::new(Some(100), Some(20)), IssuePaging { per_page: 20, limit: Some(20) });
+        assert_eq!(IssuePaging::new(None, Some(0)).limit, Some(1));
+
+        let all = IssuePaging::default();
+        assert!(all.wants_more(50, 5000));
+        assert!(!all.wants_more(49, 99));
+        let capped = IssuePaging::new(Some(50), Some(120));
+        assert!(capped.wants_more(50, 100));
+        assert!(!capped.wants_more(50, 150));
+        assert_eq!(capped.room(100), 20);
+        assert_eq!(capped.room(150), 0);
+        assert_eq!(all.room(5000), usize::MAX);
+    }
+
+    #[test]
+    fn test_open_issue_count_url() {
+        assert_eq!(
+            open_issue_count_url("o", "r"),
+            "https://api.github.com/search/issues?q=repo:o/r+state:open+type:issue&per_page=1"
+        );
+    }
+
+    #[test]
+    fn test_issues_url_state_filter() {
+        assert!(issues_url("o", "r", IssueState::default(), 50, 1).contains("state=open"));
+        assert!(issues_url("o", "r", IssueState::Closed, 50, 1).contains("state=closed"));
+        assert!(issues_url("o", "r", IssueState::All, 50, 1).contains("state=all"));
+        assert!(issues_url("o", "r", IssueState::All, 100, 3).ends_with("&per_page=100&page=3"));
+    }
+
+    #[test]
+    fn test_issue_state_deserializes_lowercase() {
+        let state: IssueState = serde_json::from_str("\"closed\"").unwrap();
+        assert_eq!(state, IssueState::Closed);
+    }
+
+    #[test]
+    fn test_parse_remote_detects_github() {
+        let remote = parse_remote("git@github.com:owner/repo.git").unwrap();
+        assert_eq!(remote.provider, GitProvider::GitHub);
+        assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("owner", "repo"));
+    }
+
+    #[test]
+    fn test_parse_remote_detects_gitlab_with_nested_groups() {
+        let remote = parse_remote("https://gitlab.com/group/sub/repo.git").unwrap();
+        assert_eq!(remote.provider, GitProvider::GitLab { host: "gitlab.com".to_string() });
+        assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("group/sub", "repo"));
+
+        let remote = parse_remote("git@gitlab.example.com:team/repo.git").unwrap();
+        assert_eq!(
+            remote.provider,
+            GitProvider::GitLab { host: "gitlab.example.com".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_get_project_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+
+        let none = get_project_remote(path.clone());
+        assert!(none.url.is_none() && none.web_url.is_none());
+
+        repo.remote("origin", "git@github.com:owner/repo.git").unwrap();
+        let remote = get_project_remote(path);
+        assert_eq!(remote.owner.as_deref(), Some("owner"));
+        assert_eq!(remote.repo.as_deref(), Some("repo"));
+        assert_eq!(remote.web_url.as_deref(), Some("https://github.com/owner/repo"));
+    }
+
+    #[test]
+    fn test_get_project_remote_unparseable_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.remote("origin", "https://example.com/owner/repo.git").unwrap();
+
+        let remote = get_project_remote(dir.path().to_string_lossy().to_string());
+        assert_eq!(remote.url.as_deref(), Some("https://example.com/owner/repo.git"));
+        assert!(remote.owner.is_none() && remote.web_url.is_none());
+    }
+
+    #[test]
+    fn test_parse_remote_unknown_host_returns_none() {
+        assert_eq!(parse_remote("https://bitbucket.org/owner/repo.git"), None);
+    }
+
     #[test]
     fn test_parse_invalid_url_returns_none() {
         assert_eq!(parse_repo_from_url("not-a-url"), None);
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use keyring::Entry;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::github::{GithubIssue, IssueLabel, IssuePaging, IssueState};
+
+/// Shared client with the same user agent and timeouts as GitHub's, but none of its
+/// GitHub-specific default headers.
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent(crate::github::USER_AGENT)
+            .timeout(crate::github::REQUEST_TIMEOUT)
+            .connect_timeout(crate::github::CONNECT_TIMEOUT)
+            .build()
+            .expect("failed to build HTTP client")
+    })
+}
+
+/// GitLab's issue shape; converted into `GithubIssue` so the frontend sees one format.
+#[derive(Debug, Deserialize)]
+struct GitlabIssue {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    state: String,
+    web_url: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+impl From<GitlabIssue> for GithubIssue {
+    fn from(issue: GitlabIssue) -> Self {
+        GithubIssue {
+            number: issue.iid,
+            title: issue.title,
+            body: issue.description,
+            // GitLab says "opened" where GitHub says "open".
+            state: if issue.state == "opened" { "open".to_string() } else { issue.state },
+            html_url: issue.web_url,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+            labels: issue
+                .labels
+                .into_iter()
+                .map(|name| IssueLabel { name, color: None, description: None })
+                .collect(),
+            already_imported: false,
+        }
+    }
+}
+
+pub fn get_gitlab_token(project_id: &str) -> Option<String> {
+    Entry::new("spawn", &format!("gitlab-{}", project_id))
+        .ok()
+        .and_then(|e| e.get_password().ok())
+}
+
+pub fn set_gitlab_token(project_id: &str, token: &str) -> Result<()> {
+    Entry::new("spawn", &format!("gitlab-{}", project_id))?
+        .set_password(token)?;
+    Ok(())
+}
+
+/// `path` is the project's full namespace path, e.g. `group/subgroup/repo`.
+pub fn issues_url(host: &str, path: &str, state: IssueState, per_page: u32, page: u32) -> String {
+    let state = match state {
+        IssueState::Open => "opened",
+        IssueState::Closed => "closed",
+        IssueState::All => "all",
+    };
+    format!(
+        "https://{}/api/v4/projects/{}/issues?state={}&per_page={}&page={}",
+        host,
+        path.replace('/', "%2F"),
+        state,
+        per_page,
+        page
+    )
+}
+
+pub async fn fetch_issues(
+    host: &str,
+    path: &str,
+    token: &str,
+    state: IssueState,
+    paging: IssuePaging,
+    mut on_page: impl FnMut(&[GithubIssue]) + Send,
+) -> Result<Vec<GithubIssue>> {
+    let map_err = |e: reqwest::Error| {
+        if e.is_timeout() {
+            anyhow::anyhow!("GitLab request timed out")
+        } else {
+            e.into()
+        }
+    };
+    let mut issues = Vec::new();
+    for page in 1.. {
+        let url = issues_url(host, path, state, paging.per_page, page);
+        let batch: Vec<GitlabIssue> = http_client()
+            .get(&url)
+            .header("PRIVATE-TOKEN", token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(map_err)?
+            .json()
+            .await
+            .map_err(map_err)?;
+        let page_len = batch.len();
+        let mut parsed: Vec<GithubIssue> = batch.into_iter().map(GithubIssue::from).collect();
+        parsed.truncate(paging.room(issues.len()));
+        on_page(&parsed);
+        issues.extend(parsed);
+        if !paging.wants_more(page_len, issues.len()) {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+#[tauri::command]
+pub fn set_project_gitlab_token(
+    project_id: String,
+    token: String,
+) -> Result<(), String> {
+    set_gitlab_token(&project_id, &token).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issues_url_encodes_namespace() {
+        assert_eq!(
+            issues_url("gitlab.com", "group/sub/repo", IssueState::Open, 50, 1),
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Frepo/issues?state=opened&per_page=50&page=1"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_issue_converts_to_common_shape() {
+        let json = r#"{"iid":7,"title":"t","description":"d","state":"opened",
+            "web_url":"https://gitlab.com/g/r/-/issues/7"}"#;
+        let issue: GithubIssue = serde_json::from_str::<GitlabIssue>(json).unwrap().into();
+        assert_eq!(issue.number, 7);
+        assert_eq!(issue.state, "open");
+        assert_eq!(issue.body.as_deref(), Some("d"));
+    }
+}
@@ -77,13 +77,44 @@ pub async fn assign_project_group_db(
     Ok(())
 }
 
+/// Move every project in `project_ids` to `group_id` (`None` = ungrouped) in one transaction.
+/// Unknown ids are skipped; returns how many projects were moved.
+pub async fn assign_projects_group_db(
+    pool: &SqlitePool,
+    project_ids: &[String],
+    group_id: Option<&str>,
+) -> Result<u64> {
+    if project_ids.is_empty() {
+        return Ok(0);
+    }
+    let mut tx = pool.begin().await?;
+    if let Some(group_id) = group_id {
+        let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM project_groups WHERE id = ?")
+            .bind(group_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            return Err(anyhow::anyhow!("Group not found"));
+        }
+    }
+    let placeholders = vec!["?"; project_ids.len()].join(", ");
+    let sql = format!("UPDATE projects SET group_id = ? WHERE id IN ({})", placeholders);
+    let mut query = sqlx::query(&sql).bind(group_id);
+    for id in project_ids {
+        query = query.bind(id);
+    }
+    let result = query.execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
 // --- Tauri commands ---
 
 #[tauri::command]
 pub async fn list_groups(
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Vec<ProjectGroup>, String> {
-    list_groups_db(&state.db).await.map_err(|e| e.to_string())
+    list_groups_db(&state.db()).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -91,7 +122,7 @@ pub async fn create_group(
     name: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<ProjectGroup, String> {
-    create_group_db(&state.db, &name).await.map_err(|e| e.to_string())
+    create_group_db(&state.db(), &name).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -100,7 +131,7 @@ pub async fn rename_group(
     name: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    rename_group_db(&state.db, &id, &name).await.map_err(|e| e.to_string())
+    rename_group_db(&state.db(), &id, &name).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -108,7 +139,7 @@ pub async fn delete_group(
     id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    delete_group_db(&state.db, &id).await.map_err(|e| e.to_string())
+    delete_group_db(&state.db(), &id).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -117,7 +148,18 @@ pub async fn assign_project_group(
     group_id: Option<String>,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    assign_project_group_db(&state.db, &project_id, group_id.as_deref())
+    assign_project_group_db(&state.db(), &project_id, group_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn assign_projects_group(
+    project_ids: Vec<String>,
+    group_id: Option<String>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<u64, String> {
+    assign_projects_group_db(&state.db(), &project_ids, group_id.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
@@ -183,4 +225,31 @@ mod tests {
         let result = delete_group_db(&pool, "nonexistent-id").await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_assign_projects_group_in_bulk() {
+        let (pool, _dir) = test_pool().await;
+        let g = create_group_db(&pool, "Work").await.unwrap();
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            let p = crate::projects::add_project_db(&pool, &format!("/tmp/{}", name), name, None)
+                .await
+                .unwrap();
+            ids.push(p.id);
+        }
+        ids.push("missing".to_string());
+
+        let moved = assign_projects_group_db(&pool, &ids, Some(&g.id)).await.unwrap();
+        assert_eq!(moved, 3);
+        let grouped: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM projects WHERE group_id = ?")
+            .bind(&g.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(grouped.0, 3);
+
+        assert_eq!(assign_projects_group_db(&pool, &ids[..2], None).await.unwrap(), 2);
+        assert_eq!(assign_projects_group_db(&pool, &[], None).await.unwrap(), 0);
+        assert!(assign_projects_group_db(&pool, &ids, Some("nope")).await.is_err());
+    }
 }
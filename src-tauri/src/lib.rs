@@ -1,22 +1,65 @@
 mod agent_configs;
+mod ansi;
 mod db;
+mod file_index;
 mod git_ops;
+mod git_watcher;
 mod group_ops;
 mod github;
+mod gitlab;
+mod notifications;
+mod profiles;
 mod projects;
+mod prompt_detect;
 mod pty_manager;
+mod session_log;
 mod sessions;
+mod settings;
+mod spawn_md;
 mod tasks;
 mod ws_server;
 
 use sqlx::SqlitePool;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+use file_index::FileIndex;
+use git_watcher::GitWatcher;
+use github::{IssueFetches, LabelCache};
 use pty_manager::PtyManager;
 
 pub struct AppState {
-    pub db: SqlitePool,
+    /// Swapped by `switch_profile`; use `db()` to get a handle.
+    db: std::sync::RwLock<SqlitePool>,
+    pub data_dir: std::path::PathBuf,
+    pub profile: std::sync::Mutex<String>,
     pub pty: PtyManager,
+    pub git_watcher: GitWatcher,
+    pub file_index: FileIndex,
+    pub git_info: projects::GitInfoCache,
+    pub issue_fetches: IssueFetches,
+    pub label_cache: LabelCache,
     pub terminal_tx: tokio::sync::broadcast::Sender<(String, Vec<u8>)>,
+    /// The WebSocket output server; `None` while stopped.
+    pub ws_server: tokio::sync::Mutex<Option<ws_server::WsServer>>,
+    /// Sessions left running by the previous run of the current profile.
+    pub orphaned_sessions: std::sync::atomic::AtomicU64,
+}
+
+impl AppState {
+    pub fn db(&self) -> SqlitePool {
+        self.db.read().unwrap().clone()
+    }
+
+    /// Install a new pool unless sessions are live, dropping the exited ones, and return the
+    /// previous pool so the caller can close it. All of it runs under the pool's write lock, so
+    /// no session can be spawned against the old pool, or dropped from the new one, meanwhile.
+    pub fn replace_db_when_idle(&self, pool: SqlitePool) -> Result<SqlitePool, SqlitePool> {
+        let mut db = self.db.write().unwrap();
+        if self.pty.has_live_sessions() {
+            return Err(pool);
+        }
+        self.pty.kill_all();
+        Ok(std::mem::replace(&mut *db, pool))
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -27,58 +70,177 @@ pub fn run() {
         .setup(|app| {
             let data_dir = app.path().app_data_dir().unwrap();
             std::fs::create_dir_all(&data_dir).unwrap();
-            let pool = tauri::async_runtime::block_on(db::init(&data_dir)).unwrap();
-            // PTY processes don't survive an app restart — clear stale sessions.
-            tauri::async_runtime::block_on(
-                sqlx::query("DELETE FROM agent_sessions").execute(&pool)
-            ).unwrap();
+            let profile = std::env::var(profiles::PROFILE_ENV)
+                .unwrap_or_else(|_| profiles::DEFAULT_PROFILE.to_string());
+            let (pool, orphaned) =
+                match tauri::async_runtime::block_on(profiles::open_profile(&data_dir, &profile)) {
+                    Ok(opened) => opened,
+                    Err(e) => {
+                        use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+                        eprintln!("Failed to open profile {}: {}", profile, e);
+                        app.dialog()
+                            .message(format!("Could not open profile \"{}\":\n{}", profile, e))
+                            .kind(MessageDialogKind::Error)
+                            .title("Spawn")
+                            .blocking_show();
+                        std::process::exit(1);
+                    }
+                };
+            let mut pty = PtyManager::new();
+            pty.log_dir = Some(data_dir.join("logs"));
+            if let Err(e) = tauri::async_runtime::block_on(settings::apply_runtime_settings(&pool, &pty)) {
+                eprintln!("Failed to apply settings: {}", e);
+            }
             let (terminal_tx, _) = tokio::sync::broadcast::channel(1024);
             app.manage(AppState {
-                db: pool,
-                pty: PtyManager::new(),
+                db: std::sync::RwLock::new(pool),
+                data_dir,
+                profile: std::sync::Mutex::new(profile),
+                pty,
+                git_watcher: GitWatcher::new(),
+                file_index: FileIndex::default(),
+                git_info: projects::GitInfoCache::default(),
+                ws_server: tokio::sync::Mutex::new(None),
+                issue_fetches: IssueFetches::default(),
+                label_cache: LabelCache::default(),
                 terminal_tx,
+                orphaned_sessions: std::sync::atomic::AtomicU64::new(orphaned),
+            });
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut tick = tokio::time::interval(std::time::Duration::from_secs(30));
+                loop {
+                    tick.tick().await;
+                    handle.state::<AppState>().pty.reap_idle_sessions();
+                }
+            });
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let interval = *handle.state::<AppState>().pty.heartbeat_interval.lock().unwrap();
+                    // Re-check periodically while disabled so a newly set interval takes effect.
+                    tokio::time::sleep(interval.unwrap_or(std::time::Duration::from_secs(5))).await;
+                    if interval.is_some() {
+                        for beat in handle.state::<AppState>().pty.take_heartbeats() {
+                            let _ = handle.emit("session-heartbeat", beat);
+                        }
+                    }
+                }
+            });
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut tick = tokio::time::interval(std::time::Duration::from_secs(2));
+                loop {
+                    tick.tick().await;
+                    for session_id in handle.state::<AppState>().pty.take_sentinel_completions() {
+                        let _ = handle.emit(
+                            "session-task-complete",
+                            pty_manager::TaskComplete { session_id, source: "sentinel" },
+                        );
+                    }
+                }
             });
-            let tx = app.state::<crate::AppState>().terminal_tx.clone();
+            let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = ws_server::start(9731, tx).await {
-                    eprintln!("WebSocket server failed: {}", e);
+                let state = handle.state::<AppState>();
+                match ws_server::start(ws_server::DEFAULT_PORT, state.terminal_tx.clone()).await {
+                    Ok(server) => *state.ws_server.lock().await = Some(server),
+                    Err(e) => eprintln!("WebSocket server failed: {}", e),
                 }
             });
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            profiles::get_profile,
+            profiles::list_profiles,
+            profiles::switch_profile,
             projects::list_projects,
+            projects::get_project,
+            projects::get_project_dashboard,
+            projects::add_project_from_github_url,
+            projects::duplicate_project,
+            projects::relocate_project,
+            db::database_health,
+            ws_server::stop_ws_server,
+            ws_server::restart_ws_server,
+            projects::search_projects,
             projects::add_project,
             projects::remove_project,
+            projects::get_project_theme,
+            projects::set_project_theme,
+            projects::get_project_layout,
+            projects::save_project_layout,
             projects::read_spawn_md,
             projects::write_spawn_md,
+            spawn_md::validate_spawn_md,
             projects::read_markdown_file,
             projects::write_markdown_file,
+            projects::replace_in_markdown_files,
             sessions::spawn_agent,
             sessions::spawn_shell,
+            sessions::spawn_shell_in_subdir,
             sessions::list_sessions,
+            sessions::get_orphaned_session_count,
+            sessions::session_counters,
+            sessions::set_session_color,
+            sessions::list_all_sessions,
             sessions::rename_agent,
+            sessions::suggest_session_name,
             sessions::kill_agent,
+            sessions::detach_session,
             sessions::resize_pty,
             sessions::write_to_agent,
             sessions::get_scrollback,
+            sessions::archive_session_scrollback,
+            sessions::get_scrollback_tail,
+            sessions::get_scrollback_since,
+            sessions::get_session_log_path,
+            sessions::get_session_spawn_info,
+            sessions::set_session_kill_when_idle,
+            sessions::set_session_readonly,
             tasks::list_tasks,
             tasks::create_task,
             tasks::update_task_status,
+            tasks::get_task_statuses,
+            tasks::set_task_statuses,
+            tasks::list_task_statuses,
+            tasks::move_task,
+            tasks::list_task_activity,
+            tasks::import_tasks_from_markdown,
+            tasks::get_task_rendered,
+            tasks::toggle_task_checkitem,
             tasks::delete_task,
             github::set_project_github_token,
+            github::set_user_github_token,
+            github::clear_user_github_token,
             github::fetch_project_issues,
+            github::cancel_project_issues,
+            github::close_github_issue,
+            github::fetch_github_issue,
+            github::count_open_issues,
+            github::list_repo_labels,
+            github::get_project_remote,
+            gitlab::set_project_gitlab_token,
             agent_configs::list_agent_configs,
+            agent_configs::parse_command_line,
             agent_configs::add_agent_config,
             agent_configs::update_agent_config,
             agent_configs::delete_agent_config,
             agent_configs::set_default_agent_config,
+            agent_configs::reorder_agent_config,
+            agent_configs::set_agent_completion_signal,
+            agent_configs::set_agent_clear_env,
+            agent_configs::set_agent_output_filter,
+            agent_configs::test_agent_config,
             group_ops::list_groups,
             group_ops::create_group,
             group_ops::rename_group,
             group_ops::delete_group,
             group_ops::assign_project_group,
+            group_ops::assign_projects_group,
             projects::open_in_finder,
+            projects::reveal_file,
             projects::open_in_vscode,
             git_ops::get_git_status,
             git_ops::git_init,
@@ -87,7 +249,43 @@ pub fn run() {
             git_ops::git_pull,
             git_ops::git_push,
             git_ops::git_commit_all,
+            git_ops::git_diff_stats,
+            git_ops::git_commit_preview,
+            git_ops::git_staged_diff,
+            git_ops::git_file_tree,
+            git_ops::get_commit_template,
+            git_ops::git_rebase,
+            git_ops::git_rebase_continue,
+            git_ops::git_rebase_abort,
+            git_ops::git_stash_list,
+            git_ops::git_stash_apply,
+            git_watcher::watch_project,
+            git_watcher::unwatch_project,
+            file_index::list_markdown_files,
+            file_index::read_project_file,
+            settings::get_setting,
+            settings::set_setting,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Reap PTY children before the process exits so no agents are orphaned.
+            if let tauri::RunEvent::Exit = event {
+                let state = app_handle.state::<AppState>();
+                // Save what is still running so its history survives the restart.
+                let db = state.db();
+                let persist = *state.pty.persist_scrollback.lock().unwrap();
+                tauri::async_runtime::block_on(async {
+                    for (id, scrollback, counters) in state.pty.scrollbacks() {
+                        let scrollback = persist.then_some(scrollback.as_slice());
+                        let saved =
+                            sessions::store_stopped_session_db(&db, &id, scrollback, counters).await;
+                        if let Err(e) = saved {
+                            eprintln!("Failed to save scrollback for {}: {}", id, e);
+                        }
+                    }
+                });
+                state.pty.kill_all();
+            }
+        });
 }
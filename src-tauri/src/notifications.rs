@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pty_manager::SessionKind;
+
+/// When a naturally exiting session emits `session-notification`. Stored as JSON under the
+/// `notification_rules` setting; missing fields take their defaults, which notify on any
+/// agent exit and never for shells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationRules {
+    pub enabled: bool,
+    pub include_shells: bool,
+    /// Only notify when the process exited with a non-zero status.
+    pub only_failures: bool,
+    /// Project ids to notify for; empty means every project.
+    pub projects: Vec<String>,
+}
+
+impl Default for NotificationRules {
+    fn default() -> Self {
+        Self { enabled: true, include_shells: false, only_failures: false, projects: Vec::new() }
+    }
+}
+
+impl NotificationRules {
+    /// `exit_code` is `None` when the status couldn't be read; that counts as a failure.
+    pub fn should_notify(&self, kind: SessionKind, project_id: &str, exit_code: Option<u32>) -> bool {
+        self.enabled
+            && (kind == SessionKind::Agent || self.include_shells)
+            && (!self.only_failures || exit_code != Some(0))
+            && (self.projects.is_empty() || self.projects.iter().any(|p| p == project_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionNotification {
+    pub session_id: String,
+    pub project_id: String,
+    pub kind: SessionKind,
+    pub exit_code: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_notify_for_agents_only() {
+        let rules = NotificationRules::default();
+        assert!(rules.should_notify(SessionKind::Agent, "p1", Some(0)));
+        assert!(!rules.should_notify(SessionKind::Shell, "", Some(1)));
+    }
+
+    #[test]
+    fn test_rules_filter_by_failure_and_project() {
+        let rules: NotificationRules =
+            serde_json::from_str(r#"{"only_failures": true, "projects": ["p1"]}"#).unwrap();
+        assert!(rules.should_notify(SessionKind::Agent, "p1", Some(2)));
+        assert!(rules.should_notify(SessionKind::Agent, "p1", None));
+        assert!(!rules.should_notify(SessionKind::Agent, "p1", Some(0)));
+        assert!(!rules.should_notify(SessionKind::Agent, "p2", Some(2)));
+
+        let off = NotificationRules { enabled: false, ..Default::default() };
+        assert!(!off.should_notify(SessionKind::Agent, "p1", Some(1)));
+    }
+}
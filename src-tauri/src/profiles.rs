@@ -0,0 +1,153 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+/// The profile whose database lives directly in the app data dir, as before profiles existed.
+pub const DEFAULT_PROFILE: &str = "default";
+/// Selects the profile to open at startup.
+pub const PROFILE_ENV: &str = "SPAWN_PROFILE";
+
+/// Data directory for a profile: the base dir for the default profile, `profiles/<name>` otherwise.
+pub fn profile_dir(base: &Path, name: &str) -> Result<PathBuf> {
+    if name == DEFAULT_PROFILE {
+        return Ok(base.to_path_buf());
+    }
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid profile name '{}': use letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    Ok(base.join("profiles").join(name))
+}
+
+pub fn list_profiles_in(base: &Path) -> Vec<String> {
+    let mut profiles: Vec<String> = std::fs::read_dir(base.join("profiles"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+/// Open (creating if needed) a profile's database. Also returns how many sessions were
+/// orphaned: PTY processes don't survive an app restart, so any session still marked running
+/// is stopped (its scrollback is kept).
+pub async fn open_profile(base: &Path, name: &str) -> Result<(SqlitePool, u64)> {
+    let dir = profile_dir(base, name)?;
+    std::fs::create_dir_all(&dir)?;
+    let pool = crate::db::init(&dir).await?;
+    let orphaned = crate::sessions::mark_orphaned_sessions_db(&pool).await?;
+    Ok((pool, orphaned))
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub fn get_profile(state: tauri::State<'_, crate::AppState>) -> String {
+    state.profile.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn list_profiles(state: tauri::State<'_, crate::AppState>) -> Vec<String> {
+    list_profiles_in(&state.data_dir)
+}
+
+/// Reopen the app against another profile's database. Refused while sessions are running,
+/// since they belong to the current profile.
+#[tauri::command]
+pub async fn switch_profile(
+    name: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    if state.pty.has_live_sessions() {
+        return Err("Stop all running sessions before switching profiles".to_string());
+    }
+    let (pool, orphaned) = open_profile(&state.data_dir, &name)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Checked again together with the swap, in case a session started while opening.
+    let old = match state.replace_db_when_idle(pool) {
+        Ok(old) => old,
+        Err(pool) => {
+            pool.close().await;
+            return Err("Stop all running sessions before switching profiles".to_string());
+        }
+    };
+    if let Err(e) = crate::settings::apply_runtime_settings(&state.db(), &state.pty).await {
+        eprintln!("Failed to apply settings: {}", e);
+    }
+
+    // Drop what else belongs to the old profile: project watchers and file listings.
+    state.git_watcher.unwatch_all();
+    state.file_index.clear();
+    *state.profile.lock().unwrap() = name;
+    state.orphaned_sessions.store(orphaned, std::sync::atomic::Ordering::Relaxed);
+    old.close().await;
+    if orphaned > 0 {
+        let _ = app.emit("sessions-orphaned", orphaned);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_profile_dir() {
+        let base = Path::new("/data");
+        assert_eq!(profile_dir(base, DEFAULT_PROFILE).unwrap(), base);
+        assert_eq!(profile_dir(base, "work").unwrap(), base.join("profiles/work"));
+        assert!(profile_dir(base, "../escape").is_err());
+        assert!(profile_dir(base, "").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_profiles_have_separate_databases() {
+        let dir = tempdir().unwrap();
+        let (default, _) = open_profile(dir.path(), DEFAULT_PROFILE).await.unwrap();
+        let (work, _) = open_profile(dir.path(), "work").await.unwrap();
+
+        crate::projects::add_project_db(&default, "/home", "Home", None).await.unwrap();
+        assert_eq!(crate::projects::list_projects_db(&default).await.unwrap().len(), 1);
+        assert!(crate::projects::list_projects_db(&work).await.unwrap().is_empty());
+        assert_eq!(list_profiles_in(dir.path()), ["default", "work"]);
+    }
+
+    #[tokio::test]
+    async fn test_reopening_profile_stops_orphaned_sessions() {
+        let dir = tempdir().unwrap();
+        let (pool, orphaned) = open_profile(dir.path(), DEFAULT_PROFILE).await.unwrap();
+        assert_eq!(orphaned, 0);
+        let project = crate::projects::add_project_db(&pool, "/home", "Home", None).await.unwrap();
+        let running = crate::sessions::create_session_db(&pool, &project.id, "A").await.unwrap();
+        crate::sessions::update_session_status_db(&pool, &running.id, "running").await.unwrap();
+        crate::sessions::save_scrollback_db(&pool, &running.id, b"history").await.unwrap();
+        crate::sessions::create_session_db(&pool, &project.id, "B").await.unwrap();
+        pool.close().await;
+
+        let (pool, orphaned) = open_profile(dir.path(), DEFAULT_PROFILE).await.unwrap();
+        assert_eq!(orphaned, 1);
+        let sessions = crate::sessions::list_sessions_db(&pool, &project.id).await.unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|s| s.status == "stopped"));
+        assert_eq!(
+            crate::sessions::load_scrollback_db(&pool, &running.id).await.unwrap(),
+            b"history"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -11,6 +12,8 @@ pub struct Project {
     pub description: Option<String>,
     pub github_repo: Option<String>,
     pub group_id: Option<String>,
+    /// Opaque terminal theme hint chosen by the frontend; not interpreted by the backend.
+    pub theme: Option<String>,
     pub created_at: i64,
 }
 
@@ -20,6 +23,54 @@ pub struct ProjectWithGit {
     pub branch: Option<String>,
     pub last_commit: Option<String>,
     pub has_spawn_md: bool,
+    /// False when the project's directory has been moved or deleted.
+    pub path_exists: bool,
+}
+
+/// Expand a leading `~`, resolve relative paths against the home directory (the app's own
+/// working directory is meaningless to users), and canonicalize. Errors unless the result is
+/// an existing directory.
+pub fn resolve_project_path(input: &str, home: &Path) -> Result<PathBuf> {
+    let input = input.trim();
+    let path = if input == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = input.strip_prefix("~/") {
+        home.join(rest)
+    } else {
+        home.join(input) // an absolute input replaces `home` entirely
+    };
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Cannot resolve {}: {}", path.display(), e))?;
+    if !resolved.is_dir() {
+        return Err(anyhow::anyhow!("{} is not a directory", resolved.display()));
+    }
+    Ok(resolved)
+}
+
+/// Resolve `path` (relative to `project_root`, or absolute) to an existing path inside the
+/// project. Errors if it doesn't exist or escapes the root, including via `..` or symlinks.
+pub fn resolve_in_project(project_root: &Path, path: &str) -> Result<PathBuf> {
+    let root = project_root
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Cannot resolve {}: {}", project_root.display(), e))?;
+    let path = root.join(path.trim());
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Cannot resolve {}: {}", path.display(), e))?;
+    if !resolved.starts_with(&root) {
+        return Err(anyhow::anyhow!("{} is outside the project", resolved.display()));
+    }
+    Ok(resolved)
+}
+
+/// Like `resolve_in_project`, but the result must be a directory.
+pub fn resolve_subdir(project_root: &Path, subdir: &str) -> Result<PathBuf> {
+    let resolved = resolve_in_project(project_root, subdir)?;
+    if !resolved.is_dir() {
+        return Err(anyhow::anyhow!("{} is not a directory", resolved.display()));
+    }
+    Ok(resolved)
 }
 
 pub async fn add_project_db(pool: &SqlitePool, path: &str, name: &str, description: Option<&str>) -> Result<Project> {
@@ -43,11 +94,167 @@ pub async fn list_projects_db(pool: &SqlitePool) -> Result<Vec<Project>> {
     Ok(projects)
 }
 
+/// Case-insensitive substring match on name, path and description. `%` and `_` in the
+/// query are matched literally.
+pub async fn search_projects_db(pool: &SqlitePool, query: &str) -> Result<Vec<Project>> {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let pattern = format!("%{}%", escaped);
+    let projects = sqlx::query_as::<_, Project>(
+        "SELECT * FROM projects
+         WHERE name LIKE ?1 ESCAPE '\\' OR path LIKE ?1 ESCAPE '\\' OR description LIKE ?1 ESCAPE '\\'
+         ORDER BY created_at",
+    )
+    .bind(pattern)
+    .fetch_all(pool)
+    .await?;
+    Ok(projects)
+}
+
+/// Its sessions go with it through the cascade, so their scrollback archives are removed here.
 pub async fn remove_project_db(pool: &SqlitePool, id: &str) -> Result<()> {
+    let archives: Vec<(Option<String>,)> = sqlx::query_as(
+        "SELECT scrollback FROM agent_sessions WHERE project_id = ? AND scrollback_encoding = 'gzip'",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
     sqlx::query("DELETE FROM projects WHERE id = ?")
         .bind(id)
         .execute(pool)
         .await?;
+    for path in archives.into_iter().filter_map(|(path,)| path) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+pub async fn get_project_db(pool: &SqlitePool, id: &str) -> Result<Project> {
+    sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Project not found"))
+}
+
+/// Point a project at a new directory, e.g. after the repository was moved. Everything keyed
+/// by project id (tasks, sessions, tokens) stays attached.
+pub async fn relocate_project_db(pool: &SqlitePool, id: &str, path: &str) -> Result<Project> {
+    let taken: Option<(String,)> = sqlx::query_as("SELECT id FROM projects WHERE path = ? AND id != ?")
+        .bind(path)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    if taken.is_some() {
+        return Err(anyhow::anyhow!("Another project already uses {}", path));
+    }
+    sqlx::query_as::<_, Project>("UPDATE projects SET path = ? WHERE id = ? RETURNING *")
+        .bind(path)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Project not found"))
+}
+
+/// Copy a project's settings and tasks into a new project at `path`. Tasks start over in
+/// the first board status, without session links or GitHub issues; sessions are not copied.
+pub async fn duplicate_project_db(pool: &SqlitePool, source_id: &str, path: &str, name: &str) -> Result<Project> {
+    let source = get_project_db(pool, source_id).await?;
+    let statuses = crate::tasks::get_task_statuses_db(pool, source_id).await?;
+    let id = Uuid::new_v4().to_string();
+    let mut tx = pool.begin().await?;
+    let project = sqlx::query_as::<_, Project>(
+        "INSERT INTO projects (id, name, path, description, group_id, theme)
+         VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(path)
+    .bind(&source.description)
+    .bind(&source.group_id)
+    .bind(&source.theme)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO project_task_statuses (project_id, status, position)
+         SELECT ?, status, position FROM project_task_statuses WHERE project_id = ?",
+    )
+    .bind(&id)
+    .bind(source_id)
+    .execute(&mut *tx)
+    .await?;
+    let tasks: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT title, description FROM tasks WHERE project_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(source_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for (title, description) in tasks {
+        sqlx::query("INSERT INTO tasks (id, project_id, title, description, status) VALUES (?, ?, ?, ?, ?)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(&id)
+            .bind(title)
+            .bind(description)
+            .bind(&statuses[0])
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(project)
+}
+
+pub async fn get_project_path_db(pool: &SqlitePool, id: &str) -> Result<String> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT path FROM projects WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    row.map(|(path,)| path)
+        .ok_or_else(|| anyhow::anyhow!("Project not found"))
+}
+
+pub async fn get_project_theme_db(pool: &SqlitePool, id: &str) -> Result<Option<String>> {
+    let row: Option<(Option<String>,)> = sqlx::query_as("SELECT theme FROM projects WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    row.map(|(theme,)| theme)
+        .ok_or_else(|| anyhow::anyhow!("Project not found"))
+}
+
+pub async fn set_project_theme_db(pool: &SqlitePool, id: &str, theme: Option<&str>) -> Result<()> {
+    let result = sqlx::query("UPDATE projects SET theme = ? WHERE id = ?")
+        .bind(theme)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Project not found"));
+    }
+    Ok(())
+}
+
+pub async fn get_project_layout_db(pool: &SqlitePool, project_id: &str) -> Result<Option<String>> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT layout FROM project_layouts WHERE project_id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|(layout,)| layout))
+}
+
+/// Store the frontend's layout blob. The backend doesn't interpret it beyond checking it is JSON.
+pub async fn save_project_layout_db(pool: &SqlitePool, project_id: &str, layout: &str) -> Result<()> {
+    serde_json::from_str::<serde_json::Value>(layout)
+        .map_err(|e| anyhow::anyhow!("Layout is not valid JSON: {}", e))?;
+    sqlx::query(
+        "INSERT INTO project_layouts (project_id, layout) VALUES (?, ?)
+         ON CONFLICT(project_id) DO UPDATE SET layout = excluded.layout, updated_at = unixepoch()",
+    )
+    .bind(project_id)
+    .bind(layout)
+    .execute(pool)
+    .await?;
     Ok(())
 }
 
@@ -61,15 +268,151 @@ pub fn get_git_info(path: &str) -> (Option<String>, Option<String>) {
     (branch, last_commit)
 }
 
+/// The per-project fields of `ProjectWithGit` that come from disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitInfo {
+    pub branch: Option<String>,
+    pub last_commit: Option<String>,
+    pub has_spawn_md: bool,
+    pub path_exists: bool,
+}
+
+impl GitInfo {
+    pub fn read(path: &str) -> Self {
+        let (branch, last_commit) = get_git_info(path);
+        let has_spawn_md = Path::new(path).join(".spawn.md").exists();
+        let path_exists = Path::new(path).is_dir();
+        Self { branch, last_commit, has_spawn_md, path_exists }
+    }
+}
+
+/// How long `list_projects` reuses a project's git info. Short, so a branch switched
+/// outside the app shows up on the next refresh; the git watcher also drops entries early.
+const GIT_INFO_TTL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Recently read `GitInfo`, keyed by project path.
+#[derive(Default)]
+pub struct GitInfoCache {
+    entries: std::sync::Mutex<std::collections::HashMap<String, (std::time::Instant, GitInfo)>>,
+}
+
+impl GitInfoCache {
+    pub fn get(&self, path: &str) -> Option<GitInfo> {
+        let entries = self.entries.lock().unwrap();
+        let (read_at, info) = entries.get(path)?;
+        (read_at.elapsed() < GIT_INFO_TTL).then(|| info.clone())
+    }
+
+    pub fn insert(&self, path: &str, info: GitInfo) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), (std::time::Instant::now(), info));
+    }
+
+    pub fn invalidate(&self, path: &str) {
+        self.entries.lock().unwrap().remove(path);
+    }
+}
+
+fn with_git_info(p: Project) -> ProjectWithGit {
+    let GitInfo { branch, last_commit, has_spawn_md, path_exists } = GitInfo::read(&p.path);
+    ProjectWithGit { project: p, branch, last_commit, has_spawn_md, path_exists }
+}
+
+/// `with_git_info` for many projects: cached entries are reused and the rest are read in
+/// parallel, since opening each repository is mostly waiting on the disk.
+async fn with_git_info_all(projects: Vec<Project>, cache: &GitInfoCache) -> Vec<ProjectWithGit> {
+    let reads: Vec<_> = projects
+        .into_iter()
+        .map(|p| {
+            let cached = cache.get(&p.path);
+            let read = cached.is_none().then(|| {
+                let path = p.path.clone();
+                tokio::task::spawn_blocking(move || GitInfo::read(&path))
+            });
+            (p, cached, read)
+        })
+        .collect();
+    let mut out = Vec::with_capacity(reads.len());
+    for (p, cached, read) in reads {
+        let info = match read {
+            Some(read) => {
+                let info = read.await.unwrap_or_default();
+                cache.insert(&p.path, info.clone());
+                info
+            }
+            None => cached.unwrap_or_default(),
+        };
+        let GitInfo { branch, last_commit, has_spawn_md, path_exists } = info;
+        out.push(ProjectWithGit { project: p, branch, last_commit, has_spawn_md, path_exists });
+    }
+    out
+}
+
 #[tauri::command]
 pub async fn list_projects(state: tauri::State<'_, crate::AppState>) -> Result<Vec<ProjectWithGit>, String> {
-    let projects = list_projects_db(&state.db).await.map_err(|e| e.to_string())?;
-    let result = projects.into_iter().map(|p| {
-        let (branch, last_commit) = get_git_info(&p.path);
-        let has_spawn_md = std::path::Path::new(&p.path).join(".spawn.md").exists();
-        ProjectWithGit { project: p, branch, last_commit, has_spawn_md }
-    }).collect();
-    Ok(result)
+    let projects = list_projects_db(&state.db()).await.map_err(|e| e.to_string())?;
+    Ok(with_git_info_all(projects, &state.git_info).await)
+}
+
+#[tauri::command]
+pub async fn get_project(
+    id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<ProjectWithGit, String> {
+    let project = get_project_db(&state.db(), &id).await.map_err(|e| e.to_string())?;
+    Ok(with_git_info(project))
+}
+
+/// Everything the project view shows on open, from one call.
+#[derive(Debug, Serialize)]
+pub struct ProjectDashboard {
+    pub project: ProjectWithGit,
+    /// Sessions not yet stopped (running, idle or failed to start).
+    pub running_sessions: usize,
+    /// Tasks per board column; columns without tasks are included with 0.
+    pub task_counts: std::collections::BTreeMap<String, usize>,
+    /// A project or user-level GitHub token is stored.
+    pub has_github_token: bool,
+}
+
+#[tauri::command]
+pub async fn get_project_dashboard(
+    id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<ProjectDashboard, String> {
+    let pool = state.db();
+    let project = get_project_db(&pool, &id).await.map_err(|e| e.to_string())?;
+    let mut sessions = crate::sessions::list_sessions_db(&pool, &id)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::sessions::overlay_live_status(&mut sessions, &state.pty);
+    let task_counts = crate::tasks::task_counts_db(&pool, &id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(ProjectDashboard {
+        project: with_git_info(project),
+        running_sessions: sessions.iter().filter(|s| s.status != "stopped").count(),
+        task_counts,
+        has_github_token: crate::github::get_github_token(&id).is_some(),
+    })
+}
+
+#[tauri::command]
+pub async fn search_projects(
+    query: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<ProjectWithGit>, String> {
+    let projects = search_projects_db(&state.db(), &query).await.map_err(|e| e.to_string())?;
+    Ok(with_git_info_all(projects, &state.git_info).await)
+}
+
+fn home_dir() -> Result<PathBuf, String> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .ok_or_else(|| "Could not determine home directory".to_string())
 }
 
 #[tauri::command]
@@ -79,14 +422,226 @@ pub async fn add_project(
     description: Option<String>,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Project, String> {
-    add_project_db(&state.db, &path, &name, description.as_deref())
+    let home = home_dir()?;
+    let path = resolve_project_path(&path, &home).map_err(|e| e.to_string())?;
+    add_project_db(&state.db(), &path.to_string_lossy(), &name, description.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `owner/repo` from a GitHub URL as copied from the browser (`/tree/main`, trailing
+/// slashes and all) or as used for cloning.
+pub fn github_repo_from_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches('/');
+    let url = match url.strip_prefix("https://github.com/") {
+        // Drop anything after owner/repo, e.g. `/tree/main` or `/issues/3`.
+        Some(path) => {
+            let owner_repo: Vec<&str> = path.splitn(3, '/').take(2).collect();
+            format!("https://github.com/{}", owner_repo.join("/"))
+        }
+        None => url.to_string(),
+    };
+    crate::github::parse_repo_from_url(&url)
+}
+
+/// A git repository directly inside one of `dirs` (or one of `dirs` itself) whose `origin`
+/// points at `owner/repo` on GitHub. Names are compared case-insensitively, like GitHub does.
+pub fn find_local_clone(dirs: &[PathBuf], owner: &str, repo: &str) -> Option<PathBuf> {
+    let matches = |path: &Path| {
+        let Some(path) = path.to_str() else { return false };
+        let Ok(url) = crate::github::origin_url(path) else { return false };
+        crate::github::parse_repo_from_url(&url)
+            .is_some_and(|(o, r)| o.eq_ignore_ascii_case(owner) && r.eq_ignore_ascii_case(repo))
+    };
+    for dir in dirs {
+        if matches(dir) {
+            return Some(dir.clone());
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else { continue };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect();
+        // Check the obvious directory name first; it is almost always the one.
+        children.sort_by_key(|p| !p.file_name().is_some_and(|n| n.eq_ignore_ascii_case(repo)));
+        if let Some(found) = children.into_iter().find(|p| matches(p)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum GithubUrlProject {
+    /// The project was created (or already existed) at a local clone.
+    Added { project: Project },
+    /// No clone was found; calling again with `clone: true` clones into `clone_path`.
+    NotFound { clone_url: String, clone_path: Option<String> },
+}
+
+/// Create a project for a GitHub repository from its URL, using an existing clone under the
+/// `project_search_dirs` setting. With `clone`, a missing repository is cloned into the
+/// first search directory instead.
+#[tauri::command]
+pub async fn add_project_from_github_url(
+    url: String,
+    clone: Option<bool>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<GithubUrlProject, String> {
+    let (owner, repo) =
+        github_repo_from_url(&url).ok_or_else(|| format!("Not a GitHub repository URL: {}", url))?;
+    use crate::settings::{get_setting_db, PROJECT_SEARCH_DIRS};
+    let dirs: Vec<PathBuf> = get_setting_db(&state.db(), PROJECT_SEARCH_DIRS)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| format!("Invalid {} setting: {}", PROJECT_SEARCH_DIRS, e))?
+        .unwrap_or_default();
+
+    let search_dirs = dirs.clone();
+    let (search_owner, search_repo) = (owner.clone(), repo.clone());
+    let found =
+        tokio::task::spawn_blocking(move || find_local_clone(&search_dirs, &search_owner, &search_repo))
+            .await
+            .map_err(|e| e.to_string())?;
+    let clone_url = format!("https://github.com/{}/{}.git", owner, repo);
+    let path = match found {
+        Some(path) => path,
+        None => {
+            let clone_path = dirs.first().map(|d| d.join(&repo));
+            let (Some(clone_path), true) = (clone_path.clone(), clone.unwrap_or(false)) else {
+                return Ok(GithubUrlProject::NotFound {
+                    clone_url,
+                    clone_path: clone_path.map(|p| p.to_string_lossy().into_owned()),
+                });
+            };
+            if clone_path.exists() {
+                return Err(format!("{} already exists", clone_path.display()));
+            }
+            let (url, dest) = (clone_url.clone(), clone_path.clone());
+            let output = tokio::task::spawn_blocking(move || {
+                std::process::Command::new("git")
+                    .arg("clone")
+                    .arg(url)
+                    .arg(dest)
+                    .stdin(std::process::Stdio::null())
+                    .output()
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+            }
+            clone_path
+        }
+    };
+
+    let path = path.canonicalize().map_err(|e| e.to_string())?.to_string_lossy().into_owned();
+    let existing = list_projects_db(&state.db())
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|p| p.path == path);
+    let project = match existing {
+        Some(project) => project,
+        None => add_project_db(&state.db(), &path, &repo, None)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    Ok(GithubUrlProject::Added { project })
+}
+
+/// Create a sibling of project `id` at `path` (which must exist), copying its `.spawn.md`
+/// (unless `path` already has one), task board and tasks. See `duplicate_project_db`.
+#[tauri::command]
+pub async fn duplicate_project(
+    id: String,
+    path: String,
+    name: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Project, String> {
+    let home = home_dir()?;
+    let path = resolve_project_path(&path, &home).map_err(|e| e.to_string())?;
+    let source_path = get_project_path_db(&state.db(), &id).await.map_err(|e| e.to_string())?;
+    let spawn_md = Path::new(&source_path).join(".spawn.md");
+    let target_md = path.join(".spawn.md");
+    if spawn_md.exists() && !target_md.exists() {
+        std::fs::copy(&spawn_md, &target_md)
+            .map_err(|e| format!("Could not copy .spawn.md: {}", e))?;
+    }
+    duplicate_project_db(&state.db(), &id, &path.to_string_lossy(), &name)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Move project `id` to `path`, which must be an existing directory (resolved like
+/// `add_project`). The old path's watcher and caches are dropped.
+#[tauri::command]
+pub async fn relocate_project(
+    id: String,
+    path: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Project, String> {
+    let path = resolve_project_path(&path, &home_dir()?).map_err(|e| e.to_string())?;
+    let old_path = get_project_path_db(&state.db(), &id).await.map_err(|e| e.to_string())?;
+    let project = relocate_project_db(&state.db(), &id, &path.to_string_lossy())
+        .await
+        .map_err(|e| e.to_string())?;
+    state.git_watcher.unwatch(&id);
+    state.file_index.invalidate(&id);
+    state.git_info.invalidate(&old_path);
+    Ok(project)
+}
+
 #[tauri::command]
 pub async fn remove_project(id: String, state: tauri::State<'_, crate::AppState>) -> Result<(), String> {
-    remove_project_db(&state.db, &id).await.map_err(|e| e.to_string())
+    state.git_watcher.unwatch(&id);
+    state.file_index.invalidate(&id);
+    remove_project_db(&state.db(), &id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_project_theme(
+    id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Option<String>, String> {
+    get_project_theme_db(&state.db(), &id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_project_theme(
+    id: String,
+    theme: Option<String>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    set_project_theme_db(&state.db(), &id, theme.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_project_layout(
+    project_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Option<String>, String> {
+    get_project_layout_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_project_layout(
+    project_id: String,
+    layout: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    save_project_layout_db(&state.db(), &project_id, &layout)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -101,8 +656,26 @@ pub fn write_spawn_md(path: String, content: String) -> Result<(), String> {
     std::fs::write(file_path, content).map_err(|e| e.to_string())
 }
 
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Decode file contents, dropping a UTF-8 BOM. Invalid UTF-8 is an error unless `lossy`,
+/// in which case bad bytes become U+FFFD so the file is at least viewable.
+fn decode_text(bytes: &[u8], lossy: bool) -> Result<String, String> {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_string()),
+        Err(_) if lossy => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        Err(e) => Err(format!("File is not valid UTF-8: {}", e)),
+    }
+}
+
+/// `None` when the file doesn't exist. See `decode_text` for `lossy`.
 #[tauri::command]
-pub fn read_markdown_file(project_path: String, filename: String) -> Result<Option<String>, String> {
+pub fn read_markdown_file(
+    project_path: String,
+    filename: String,
+    lossy: Option<bool>,
+) -> Result<Option<String>, String> {
     let base = std::fs::canonicalize(&project_path)
         .map_err(|e| format!("Invalid project path: {}", e))?;
     let target = base.join(&filename);
@@ -110,18 +683,131 @@ pub fn read_markdown_file(project_path: String, filename: String) -> Result<Opti
     if !resolved.starts_with(&base) {
         return Err("Access denied: path outside project directory".to_string());
     }
-    Ok(std::fs::read_to_string(&target).ok())
+    match std::fs::read(&target) {
+        Ok(bytes) => decode_text(&bytes, lossy.unwrap_or(false)).map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
+/// `bom` adds (`true`) or drops (`false`) a UTF-8 BOM; unset keeps whatever the existing
+/// file had, so round-tripping through the editor doesn't change it.
 #[tauri::command]
-pub fn write_markdown_file(project_path: String, filename: String, content: String) -> Result<(), String> {
+pub fn write_markdown_file(
+    project_path: String,
+    filename: String,
+    content: String,
+    bom: Option<bool>,
+) -> Result<(), String> {
     let base = std::fs::canonicalize(&project_path)
         .map_err(|e| format!("Invalid project path: {}", e))?;
     let target = base.join(&filename);
     if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
         return Err("Access denied: invalid filename".to_string());
     }
-    std::fs::write(target, content).map_err(|e| e.to_string())
+    let bom = bom.unwrap_or_else(|| {
+        std::fs::read(&target).is_ok_and(|existing| existing.starts_with(UTF8_BOM))
+    });
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let mut bytes = Vec::with_capacity(content.len() + UTF8_BOM.len());
+    if bom {
+        bytes.extend_from_slice(UTF8_BOM);
+    }
+    bytes.extend_from_slice(content.as_bytes());
+    std::fs::write(target, bytes).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkdownReplacement {
+    pub path: String,
+    pub matches: usize,
+}
+
+/// Directories never descended into when walking a project tree.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist"];
+
+/// Recursively collect `.md` files under `base`, skipping hidden and build directories.
+/// Symlinks are not followed, so the walk can never leave the project directory.
+pub fn collect_markdown_files(base: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![base.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(file_type) = entry.file_type() else { continue };
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                    stack.push(entry.path());
+                }
+            } else if file_type.is_file() && name.to_lowercase().ends_with(".md") {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Replace `search` with `replacement` in every markdown file under `base`. `search` is a
+/// literal unless `is_regex` is set, in which case `replacement` may use `$1`-style groups.
+/// With `dry_run` nothing is written; the returned counts describe what would change.
+pub fn replace_in_markdown(
+    base: &Path,
+    search: &str,
+    replacement: &str,
+    is_regex: bool,
+    dry_run: bool,
+) -> Result<Vec<MarkdownReplacement>> {
+    if search.is_empty() {
+        return Err(anyhow::anyhow!("Search string cannot be empty"));
+    }
+    let pattern = if is_regex { search.to_string() } else { regex::escape(search) };
+    let re = regex::Regex::new(&pattern)?;
+
+    let mut changed = Vec::new();
+    for file in collect_markdown_files(base) {
+        let Ok(content) = std::fs::read_to_string(&file) else { continue };
+        let matches = re.find_iter(&content).count();
+        if matches == 0 {
+            continue;
+        }
+        if !dry_run {
+            let updated = if is_regex {
+                re.replace_all(&content, replacement)
+            } else {
+                re.replace_all(&content, regex::NoExpand(replacement))
+            };
+            std::fs::write(&file, updated.as_ref())?;
+        }
+        let rel = file.strip_prefix(base).unwrap_or(&file);
+        changed.push(MarkdownReplacement {
+            path: rel.to_string_lossy().to_string(),
+            matches,
+        });
+    }
+    Ok(changed)
+}
+
+#[tauri::command]
+pub fn replace_in_markdown_files(
+    project_path: String,
+    search: String,
+    replacement: String,
+    is_regex: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<Vec<MarkdownReplacement>, String> {
+    let base = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path: {}", e))?;
+    replace_in_markdown(
+        &base,
+        &search,
+        &replacement,
+        is_regex.unwrap_or(false),
+        dry_run.unwrap_or(true),
+    )
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -133,6 +819,31 @@ pub fn open_in_finder(path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Show a project file selected in the OS file manager. Where selecting isn't supported
+/// (Linux has no common way to do it), the containing directory is opened instead.
+#[tauri::command]
+pub fn reveal_file(project_path: String, path: String) -> Result<(), String> {
+    let target = resolve_in_project(Path::new(&project_path), &path).map_err(|e| e.to_string())?;
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(&target);
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut selection = std::ffi::OsString::from("/select,");
+        selection.push(&target);
+        let mut command = std::process::Command::new("explorer");
+        command.arg(selection);
+        command
+    } else {
+        let dir = if target.is_dir() { target.as_path() } else { target.parent().unwrap_or(&target) };
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(dir);
+        command
+    };
+    command.spawn().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn open_in_vscode(path: String) -> Result<(), String> {
     std::process::Command::new("code")
@@ -148,12 +859,146 @@ mod tests {
     use crate::db;
     use tempfile::tempdir;
 
+    fn bench_repos(count: usize) -> (tempfile::TempDir, Vec<Project>) {
+        let dir = tempdir().unwrap();
+        let projects = (0..count)
+            .map(|i| {
+                let path = dir.path().join(format!("repo{}", i));
+                let repo = git2::Repository::init(&path).unwrap();
+                let sig = git2::Signature::now("t", "t@example.com").unwrap();
+                let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+                repo.commit(Some("HEAD"), &sig, &sig, &format!("commit {}", i), &tree, &[]).unwrap();
+                Project {
+                    id: format!("p{}", i),
+                    name: format!("repo{}", i),
+                    path: path.to_string_lossy().into_owned(),
+                    description: None,
+                    github_repo: None,
+                    group_id: None,
+                    theme: None,
+                    created_at: 0,
+                }
+            })
+            .collect();
+        (dir, projects)
+    }
+
+    #[tokio::test]
+    async fn test_parallel_git_info_matches_sequential() {
+        let (_dir, projects) = bench_repos(5);
+        let cache = GitInfoCache::default();
+        let parallel = with_git_info_all(projects.clone(), &cache).await;
+        for (p, listed) in projects.into_iter().zip(&parallel) {
+            assert_eq!(listed.project.id, p.id);
+            let expected = GitInfo::read(&p.path);
+            assert_eq!(listed.last_commit, expected.last_commit);
+            assert_eq!(cache.get(&p.path), Some(expected));
+        }
+        cache.invalidate(&parallel[0].project.path);
+        assert_eq!(cache.get(&parallel[0].project.path), None);
+    }
+
+    #[test]
+    fn test_git_info_reports_missing_path() {
+        let (dir, projects) = bench_repos(1);
+        assert!(GitInfo::read(&projects[0].path).path_exists);
+        drop(dir);
+        let info = GitInfo::read(&projects[0].path);
+        assert!(!info.path_exists);
+        assert_eq!(info.branch, None);
+    }
+
+    /// `cargo test bench_list_git_info -- --ignored --nocapture`
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn bench_list_git_info() {
+        let (_dir, projects) = bench_repos(30);
+        let start = std::time::Instant::now();
+        let _: Vec<_> = projects.iter().cloned().map(with_git_info).collect();
+        println!("sequential:   {:?}", start.elapsed());
+
+        let cache = GitInfoCache::default();
+        let start = std::time::Instant::now();
+        with_git_info_all(projects.clone(), &cache).await;
+        println!("parallel:     {:?}", start.elapsed());
+        let start = std::time::Instant::now();
+        with_git_info_all(projects, &cache).await;
+        println!("cached:       {:?}", start.elapsed());
+    }
+
     async fn test_pool() -> (SqlitePool, tempfile::TempDir) {
         let dir = tempdir().unwrap();
         let pool = db::init(dir.path()).await.unwrap();
         (pool, dir)
     }
 
+    #[test]
+    fn test_resolve_subdir_stays_in_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("repo");
+        std::fs::create_dir_all(root.join("packages/web")).unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        let canonical = root.canonicalize().unwrap();
+
+        assert_eq!(resolve_subdir(&root, "packages/web").unwrap(), canonical.join("packages/web"));
+        assert_eq!(resolve_subdir(&root, "").unwrap(), canonical);
+        assert!(resolve_subdir(&root, "..").is_err());
+        assert!(resolve_subdir(&root, "packages/../../").is_err());
+        assert!(resolve_subdir(&root, dir.path().to_str().unwrap()).is_err());
+        assert!(resolve_subdir(&root, "README.md").is_err());
+        assert!(resolve_subdir(&root, "missing").is_err());
+    }
+
+    #[test]
+    fn test_resolve_in_project_accepts_files_but_not_escaping_links() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let resolved = resolve_in_project(&root, "src/main.rs").unwrap();
+        assert_eq!(resolved, root.canonicalize().unwrap().join("src/main.rs"));
+        assert!(resolve_in_project(&root, "src/../../secret.txt").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link")).unwrap();
+            assert!(resolve_in_project(&root, "link").is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relocate_project_keeps_tasks() {
+        let (pool, _dir) = test_pool().await;
+        let project = add_project_db(&pool, "/tmp/old", "One", None).await.unwrap();
+        add_project_db(&pool, "/tmp/other", "Two", None).await.unwrap();
+        crate::tasks::create_task_db(&pool, &project.id, "Task", None, None).await.unwrap();
+
+        let moved = relocate_project_db(&pool, &project.id, "/tmp/new").await.unwrap();
+        assert_eq!(moved.id, project.id);
+        assert_eq!(moved.path, "/tmp/new");
+        assert_eq!(crate::tasks::list_tasks_db(&pool, &project.id).await.unwrap().len(), 1);
+
+        let err = relocate_project_db(&pool, &project.id, "/tmp/other").await.unwrap_err();
+        assert!(err.to_string().contains("already uses"));
+        // Relocating onto its own path is a no-op, not a conflict.
+        relocate_project_db(&pool, &project.id, "/tmp/new").await.unwrap();
+        let err = relocate_project_db(&pool, "missing", "/tmp/x").await.unwrap_err();
+        assert_eq!(err.to_string(), "Project not found");
+    }
+
+    #[tokio::test]
+    async fn test_get_project() {
+        let (pool, _dir) = test_pool().await;
+        let added = add_project_db(&pool, "/tmp/one", "One", None).await.unwrap();
+        add_project_db(&pool, "/tmp/two", "Two", None).await.unwrap();
+
+        let project = get_project_db(&pool, &added.id).await.unwrap();
+        assert_eq!(project.name, "One");
+        let err = get_project_db(&pool, "missing").await.unwrap_err();
+        assert_eq!(err.to_string(), "Project not found");
+    }
+
     #[tokio::test]
     async fn test_add_project() {
         let (pool, _dir) = test_pool().await;
@@ -172,6 +1017,38 @@ mod tests {
         assert_eq!(projects.len(), 1);
     }
 
+    #[test]
+    fn test_resolve_project_path() {
+        let home = tempdir().unwrap();
+        let home_path = home.path().canonicalize().unwrap();
+        std::fs::create_dir_all(home.path().join("code/foo")).unwrap();
+        std::fs::write(home.path().join("file.txt"), "").unwrap();
+
+        assert_eq!(resolve_project_path("~", home.path()).unwrap(), home_path);
+        assert_eq!(resolve_project_path("~/code/foo", home.path()).unwrap(), home_path.join("code/foo"));
+        assert_eq!(resolve_project_path("code/./foo/", home.path()).unwrap(), home_path.join("code/foo"));
+        let abs = home_path.join("code").to_string_lossy().into_owned();
+        assert_eq!(resolve_project_path(&abs, Path::new("/elsewhere")).unwrap(), home_path.join("code"));
+
+        assert!(resolve_project_path("~/missing", home.path()).is_err());
+        assert!(resolve_project_path("~/file.txt", home.path()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_projects() {
+        let (pool, _dir) = test_pool().await;
+        add_project_db(&pool, "/work/spawn", "Spawn", Some("Agent manager")).await.unwrap();
+        add_project_db(&pool, "/work/blog", "Blog", None).await.unwrap();
+        add_project_db(&pool, "/work/100_pct", "Stats", None).await.unwrap();
+
+        let names = |ps: Vec<Project>| ps.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(names(search_projects_db(&pool, "spawn").await.unwrap()), ["Spawn"]);
+        assert_eq!(names(search_projects_db(&pool, "AGENT").await.unwrap()), ["Spawn"]);
+        assert_eq!(names(search_projects_db(&pool, "/work/").await.unwrap()).len(), 3);
+        assert_eq!(names(search_projects_db(&pool, "0_p").await.unwrap()), ["Stats"]);
+        assert!(search_projects_db(&pool, "_x").await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_remove_project() {
         let (pool, _dir) = test_pool().await;
@@ -181,4 +1058,179 @@ mod tests {
         let projects = list_projects_db(&pool).await.unwrap();
         assert_eq!(projects.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_remove_project_deletes_scrollback_archives() {
+        use crate::sessions::{archive_scrollback_db, create_session_db, save_scrollback_db};
+
+        let (pool, data_dir) = test_pool().await;
+        let p = add_project_db(&pool, "/work/a", "A", None).await.unwrap();
+        let session = create_session_db(&pool, &p.id, "S1").await.unwrap();
+        save_scrollback_db(&pool, &session.id, b"output").await.unwrap();
+        let archive = archive_scrollback_db(&pool, &session.id, &data_dir.path().join("scrollback"))
+            .await
+            .unwrap();
+        assert!(std::path::Path::new(&archive.path).exists());
+
+        remove_project_db(&pool, &p.id).await.unwrap();
+        assert!(!std::path::Path::new(&archive.path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_project_theme_roundtrip() {
+        let (pool, _dir) = test_pool().await;
+        let dir = tempdir().unwrap();
+        let p = add_project_db(&pool, dir.path().to_str().unwrap(), "P1", None).await.unwrap();
+        assert_eq!(p.theme, None);
+
+        set_project_theme_db(&pool, &p.id, Some("solarized-dark")).await.unwrap();
+        assert_eq!(
+            get_project_theme_db(&pool, &p.id).await.unwrap().as_deref(),
+            Some("solarized-dark")
+        );
+        let projects = list_projects_db(&pool).await.unwrap();
+        assert_eq!(projects[0].theme.as_deref(), Some("solarized-dark"));
+
+        set_project_theme_db(&pool, &p.id, None).await.unwrap();
+        assert_eq!(get_project_theme_db(&pool, &p.id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_project_layout_roundtrip_and_cleanup() {
+        let (pool, _dir) = test_pool().await;
+        let p = add_project_db(&pool, "/work/a", "A", None).await.unwrap();
+
+        assert_eq!(get_project_layout_db(&pool, &p.id).await.unwrap(), None);
+        save_project_layout_db(&pool, &p.id, r#"{"panes":[]}"#).await.unwrap();
+        save_project_layout_db(&pool, &p.id, r#"{"panes":["s1"]}"#).await.unwrap();
+        assert_eq!(
+            get_project_layout_db(&pool, &p.id).await.unwrap().as_deref(),
+            Some(r#"{"panes":["s1"]}"#)
+        );
+        assert!(save_project_layout_db(&pool, &p.id, "not json").await.is_err());
+        assert!(save_project_layout_db(&pool, "missing", "{}").await.is_err());
+
+        remove_project_db(&pool, &p.id).await.unwrap();
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM project_layouts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_theme_on_missing_project_errors() {
+        let (pool, _dir) = test_pool().await;
+        assert!(set_project_theme_db(&pool, "nope", Some("x")).await.is_err());
+        assert!(get_project_theme_db(&pool, "nope").await.is_err());
+    }
+
+    #[test]
+    fn test_markdown_bom_and_lossy_decoding() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let read = |name: &str, lossy| read_markdown_file(path.clone(), name.to_string(), lossy);
+
+        std::fs::write(dir.path().join("bom.md"), b"\xEF\xBB\xBF# Title").unwrap();
+        assert_eq!(read("bom.md", None).unwrap().as_deref(), Some("# Title"));
+        // Saving without an explicit choice keeps the BOM.
+        write_markdown_file(path.clone(), "bom.md".into(), "# New".into(), None).unwrap();
+        assert_eq!(std::fs::read(dir.path().join("bom.md")).unwrap(), b"\xEF\xBB\xBF# New");
+        write_markdown_file(path.clone(), "bom.md".into(), "# New".into(), Some(false)).unwrap();
+        assert_eq!(std::fs::read(dir.path().join("bom.md")).unwrap(), b"# New");
+
+        std::fs::write(dir.path().join("latin1.md"), b"caf\xE9").unwrap();
+        assert!(read("latin1.md", None).is_err());
+        assert_eq!(read("latin1.md", Some(true)).unwrap().as_deref(), Some("caf\u{fffd}"));
+        assert_eq!(read("missing.md", None).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_project_resets_tasks() {
+        let (pool, _dir) = test_pool().await;
+        let source = add_project_db(&pool, "/tmp/a", "A", Some("desc")).await.unwrap();
+        let statuses = ["backlog".to_string(), "done".to_string()];
+        crate::tasks::set_task_statuses_db(&pool, &source.id, &statuses).await.unwrap();
+        let task = crate::tasks::create_task_db(&pool, &source.id, "Ship", None, Some("done"))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE tasks SET source = 'github', github_issue_number = 7 WHERE id = ?")
+            .bind(&task.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let copy = duplicate_project_db(&pool, &source.id, "/tmp/b", "B").await.unwrap();
+        assert_eq!((copy.path.as_str(), copy.description.as_deref()), ("/tmp/b", Some("desc")));
+        let tasks = crate::tasks::list_tasks_db(&pool, &copy.id).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!((tasks[0].title.as_str(), tasks[0].status.as_str()), ("Ship", "backlog"));
+        assert_eq!((tasks[0].source.as_str(), tasks[0].github_issue_number), ("custom", None));
+        assert_eq!(crate::tasks::get_task_statuses_db(&pool, &copy.id).await.unwrap(), statuses);
+        assert!(duplicate_project_db(&pool, "missing", "/tmp/c", "C").await.is_err());
+    }
+
+    #[test]
+    fn test_github_repo_from_browser_url() {
+        let expected = Some(("octo".to_string(), "spawn".to_string()));
+        assert_eq!(github_repo_from_url("https://github.com/octo/spawn/tree/main/src"), expected);
+        assert_eq!(github_repo_from_url(" https://github.com/octo/spawn/ "), expected);
+        assert_eq!(github_repo_from_url("git@github.com:octo/spawn.git"), expected);
+        assert_eq!(github_repo_from_url("https://gitlab.com/octo/spawn"), None);
+    }
+
+    #[test]
+    fn test_find_local_clone_by_origin() {
+        let dir = tempdir().unwrap();
+        let origins = [
+            ("other", "https://github.com/octo/other.git"),
+            ("checkout", "git@github.com:Octo/Spawn.git"),
+        ];
+        for (name, origin) in origins {
+            let repo = git2::Repository::init(dir.path().join(name)).unwrap();
+            repo.remote("origin", origin).unwrap();
+        }
+        let dirs = [dir.path().to_path_buf()];
+        assert_eq!(find_local_clone(&dirs, "octo", "spawn"), Some(dir.path().join("checkout")));
+        assert_eq!(find_local_clone(&dirs, "octo", "missing"), None);
+    }
+
+    #[test]
+    fn test_collect_markdown_files_skips_hidden_and_build_dirs() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("docs")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.path().join("README.md"), "a").unwrap();
+        std::fs::write(dir.path().join("docs/guide.md"), "b").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "c").unwrap();
+        std::fs::write(dir.path().join(".git/x.md"), "d").unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/README.md"), "e").unwrap();
+
+        let files = collect_markdown_files(dir.path());
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_replace_in_markdown_dry_run_does_not_write() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "foo and foo").unwrap();
+
+        let changed = replace_in_markdown(dir.path(), "foo", "bar", false, true).unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].matches, 2);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.md")).unwrap(), "foo and foo");
+    }
+
+    #[test]
+    fn test_replace_in_markdown_literal_and_regex() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "v1.2 costs $5").unwrap();
+
+        replace_in_markdown(dir.path(), "$5", "$6", false, false).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.md")).unwrap(), "v1.2 costs $6");
+
+        replace_in_markdown(dir.path(), r"v(\d)\.(\d)", "v$1.$2.0", true, false).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.md")).unwrap(), "v1.2.0 costs $6");
+    }
 }
@@ -0,0 +1,91 @@
+use crate::ansi::{strip_ansi, Utf8Stream};
+
+/// Patterns used when prompt detection is enabled but no custom list has been saved.
+/// Matching is a case-insensitive substring test against the last line of output.
+pub const DEFAULT_PROMPT_PATTERNS: &[&str] = &["[y/n]", "(y/n)", "[yes/no]", "(yes/no)", "? (y)"];
+
+/// Keep only this many bytes of recent output when looking for a prompt.
+const TAIL_LIMIT: usize = 512;
+
+/// Watches the tail of a session's output for a line that looks like a confirmation prompt.
+#[derive(Default)]
+pub struct PromptDetector {
+    tail: String,
+    last_emitted: Option<String>,
+    utf8: Utf8Stream,
+}
+
+impl PromptDetector {
+    /// Feed a chunk of raw PTY output. Returns the prompt line the first time the output
+    /// ends on a line matching one of `patterns`; repeated redraws of the same prompt are ignored.
+    pub fn feed(&mut self, data: &[u8], patterns: &[String]) -> Option<String> {
+        if patterns.is_empty() {
+            return None;
+        }
+        self.tail.push_str(&strip_ansi(&self.utf8.push(data)));
+        if self.tail.len() > TAIL_LIMIT {
+            let mut cut = self.tail.len() - TAIL_LIMIT;
+            while !self.tail.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.tail.drain(..cut);
+        }
+
+        let line = self
+            .tail
+            .trim_end()
+            .rsplit(['\n', '\r'])
+            .next()
+            .unwrap_or("")
+            .trim();
+        let lower = line.to_lowercase();
+        let matched = !line.is_empty()
+            && patterns
+                .iter()
+                .any(|p| !p.is_empty() && lower.contains(&p.to_lowercase()));
+        if !matched {
+            self.last_emitted = None;
+            return None;
+        }
+        if self.last_emitted.as_deref() == Some(line) {
+            return None;
+        }
+        self.last_emitted = Some(line.to_string());
+        self.last_emitted.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Vec<String> {
+        DEFAULT_PROMPT_PATTERNS.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detects_prompt_on_last_line() {
+        let mut d = PromptDetector::default();
+        let hit = d.feed(b"Writing files...\r\nProceed? [y/N] ", &defaults());
+        assert_eq!(hit.as_deref(), Some("Proceed? [y/N]"));
+    }
+
+    #[test]
+    fn test_same_prompt_is_reported_once() {
+        let mut d = PromptDetector::default();
+        assert!(d.feed(b"Continue (y/n)?", &defaults()).is_some());
+        assert!(d.feed(b"", &defaults()).is_none());
+    }
+
+    #[test]
+    fn test_prompt_followed_by_output_is_ignored() {
+        let mut d = PromptDetector::default();
+        assert!(d.feed(b"Proceed? [y/N]\r\nok, continuing\r\n", &defaults()).is_none());
+    }
+
+    #[test]
+    fn test_disabled_without_patterns() {
+        let mut d = PromptDetector::default();
+        assert!(d.feed(b"Proceed? [y/N]", &[]).is_none());
+    }
+}
@@ -1,72 +1,878 @@
-use std::collections::HashMap;
-use std::io::Read;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
+use crate::ansi::TitleScanner;
+use crate::notifications::{NotificationRules, SessionNotification};
+use crate::prompt_detect::PromptDetector;
+use crate::session_log::{self, SessionLog};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SessionStatus {
     Running,
     Idle,
     Stopped,
+    /// Still alive but printed nothing within its startup timeout.
+    FailedToStart,
+}
+
+impl SessionStatus {
+    /// The lowercase form stored in `agent_sessions.status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Running => "running",
+            SessionStatus::Idle => "idle",
+            SessionStatus::Stopped => "stopped",
+            SessionStatus::FailedToStart => "failed_to_start",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    Agent,
+    Shell,
 }
 
 pub struct PtySession {
     pub id: String,
     pub project_id: String,
+    pub kind: SessionKind,
     pub status: SessionStatus,
     pub scrollback: Vec<u8>,
     pub writer: Box<dyn std::io::Write + Send>,
     pub child: Box<dyn portable_pty::Child + Send + Sync>,
-    pub master: Box<dyn portable_pty::MasterPty + Send>,
+    /// `None` for piped sessions, which have no terminal to resize.
+    pub master: Option<Box<dyn portable_pty::MasterPty + Send>>,
+    /// Last time output was read or input was written.
+    pub last_activity: Instant,
+    /// Opt-in: kill this session once it has been idle longer than the idle timeout.
+    pub kill_when_idle: bool,
+    /// Observer mode: `write_to_session` rejects input while set.
+    pub readonly: bool,
+    /// Output bytes read since the last `session-heartbeat`.
+    pub bytes_since_heartbeat: u64,
+    /// Totals since the session was created.
+    pub counters: SessionCounters,
+    /// Last window title the program set via an OSC 0/2 sequence.
+    pub terminal_title: Option<String>,
+    /// Output string that signals the agent finished its task.
+    pub completion_marker: Option<String>,
+    /// File whose appearance signals the agent finished its task; removed once seen.
+    pub sentinel_file: Option<PathBuf>,
+}
+
+/// Bytes a session has produced (`bytes_out`) and been sent as input (`bytes_in`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SessionCounters {
+    pub bytes_out: u64,
+    pub bytes_in: u64,
 }
 
+/// Bytes of `scrollback` after `offset` and the total length; the whole buffer when the
+/// offset is past the end.
+pub fn scrollback_delta(scrollback: &[u8], offset: usize) -> (Vec<u8>, usize) {
+    let start = if offset > scrollback.len() { 0 } else { offset };
+    (scrollback[start..].to_vec(), scrollback.len())
+}
+
+/// Largest terminal accepted by `resize_session`; anything bigger is clamped.
+pub const MAX_COLS: u16 = 1000;
+pub const MAX_ROWS: u16 = 500;
+
+/// A zero dimension (e.g. a hidden pane that measured as empty) is ignored rather than
+/// forwarded; oversized values are clamped to `MAX_COLS`/`MAX_ROWS`.
+pub fn clamp_size(cols: u16, rows: u16) -> Option<(u16, u16)> {
+    if cols == 0 || rows == 0 {
+        return None;
+    }
+    Some((cols.min(MAX_COLS), rows.min(MAX_ROWS)))
+}
+
+/// Returned when writing to a session whose process has already exited, so the frontend can
+/// offer a restart instead of surfacing an IO error.
+#[derive(Debug)]
+pub struct SessionExited;
+
+impl std::fmt::Display for SessionExited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "session exited")
+    }
+}
+
+impl std::error::Error for SessionExited {}
+
+/// Returned when writing to a session set read-only with `set_readonly`.
+#[derive(Debug)]
+pub struct SessionReadOnly;
+
+impl std::fmt::Display for SessionReadOnly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "session is read-only; make it writable to send input")
+    }
+}
+
+impl std::error::Error for SessionReadOnly {}
+
+/// Default cap on a single write to a session; far above any realistic paste.
+pub const DEFAULT_MAX_WRITE_BYTES: usize = 1024 * 1024;
+
+/// Returned for a single write larger than `PtyManager::max_write_bytes`.
+#[derive(Debug)]
+pub struct WriteTooLarge {
+    pub len: usize,
+    pub max: usize,
+}
+
+impl std::fmt::Display for WriteTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "write of {} bytes exceeds the {} byte limit", self.len, self.max)
+    }
+}
+
+impl std::error::Error for WriteTooLarge {}
+
+impl PtySession {
+    fn has_exited(&mut self) -> bool {
+        matches!(self.status, SessionStatus::Stopped) || matches!(self.child.try_wait(), Ok(Some(_)))
+    }
+
+    fn mark_active(&mut self) {
+        self.last_activity = Instant::now();
+        if let SessionStatus::Idle | SessionStatus::FailedToStart = self.status {
+            self.status = SessionStatus::Running;
+        }
+    }
+}
+
+/// The session's exit status once the child is gone. Output EOF can arrive a moment before
+/// the process is reaped, so this waits briefly before giving up, locking the session only
+/// for each check so writes and status reads aren't held up meanwhile.
+fn wait_exit_code(session: &SharedSession) -> Option<u32> {
+    for _ in 0..20 {
+        if let Ok(Some(status)) = session.lock().ok()?.child.try_wait() {
+            return Some(status.exit_code());
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+    None
+}
+
+/// Payload of the periodic `session-heartbeat` event, sent for every live session whether
+/// or not it produced output.
+#[derive(Debug, Clone, Serialize)]
+pub struct Heartbeat {
+    pub session_id: String,
+    pub bytes: u64,
+}
+
+/// The only variables an agent spawned with a cleared environment gets, copied from the app's
+/// environment when set: `PATH` and `HOME` so tools resolve and find their config, `TERM`
+/// (`xterm-256color` if unset) so terminal programs render. Anything else has to be passed
+/// explicitly.
+pub const ISOLATED_ENV_VARS: &[&str] = &["PATH", "HOME", "TERM"];
+
+/// The environment for an agent config with `clear_env` set.
+pub fn isolated_env() -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = ISOLATED_ENV_VARS
+        .iter()
+        .filter_map(|name| Some((name.to_string(), std::env::var(name).ok()?)))
+        .collect();
+    env.entry("TERM".to_string()).or_insert_with(|| "xterm-256color".to_string());
+    env
+}
+
+/// How an agent signals it is done with its task without exiting. See `session-task-complete`.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionSignal {
+    pub marker: Option<String>,
+    /// Relative to the session's working directory.
+    pub sentinel_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskComplete {
+    pub session_id: String,
+    /// `"marker"` or `"sentinel"`.
+    pub source: &'static str,
+}
+
+/// Counts occurrences of a marker in a byte stream, including ones split across reads.
+struct MarkerScanner {
+    marker: Vec<u8>,
+    carry: Vec<u8>,
+}
+
+impl MarkerScanner {
+    fn new(marker: &str) -> Option<Self> {
+        (!marker.is_empty()).then(|| Self { marker: marker.as_bytes().to_vec(), carry: Vec::new() })
+    }
+
+    fn feed(&mut self, data: &[u8]) -> usize {
+        self.carry.extend_from_slice(data);
+        let mut count = 0;
+        let mut i = 0;
+        while i + self.marker.len() <= self.carry.len() {
+            if self.carry[i..].starts_with(&self.marker) {
+                count += 1;
+                i += self.marker.len();
+            } else {
+                i += 1;
+            }
+        }
+        // The unscanned rest is shorter than the marker: it may be the start of one that
+        // straddles the next read.
+        self.carry.drain(..i);
+        count
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleChanged {
+    pub session_id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AwaitingInput {
+    pub session_id: String,
+    pub prompt: String,
+}
+
+/// Reads the chunks sent on `rx` back to back. Reaches EOF once every sender is gone.
+struct ChannelReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+    pos: usize,
+}
+
+impl ChannelReader {
+    fn new(rx: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self { rx, pending: Vec::new(), pos: 0 }
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.pending.len() {
+            match self.rx.recv() {
+                Ok(chunk) => {
+                    self.pending = chunk;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len() - self.pos);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Merges several byte streams into one reader, a whole line at a time, so stdout and stderr
+/// of a piped session never interleave mid-line. A stream's unterminated last line gets a `\n`
+/// so the next stream's line isn't glued onto it. Reaches EOF once every stream has closed.
+struct LineMerger(ChannelReader);
+
+impl LineMerger {
+    fn new(streams: Vec<Box<dyn Read + Send>>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        for stream in streams {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                let mut stream = BufReader::new(stream);
+                loop {
+                    let mut line = Vec::new();
+                    match stream.read_until(b'\n', &mut line) {
+                        Ok(0) | Err(_) => break,
+                        Ok(_) => {
+                            if !line.ends_with(b"\n") {
+                                line.push(b'\n');
+                            }
+                            if tx.send(line).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        Self(ChannelReader::new(rx))
+    }
+}
+
+impl Read for LineMerger {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+/// Written into a session's output when its filter can't take any more, before the raw
+/// output that follows.
+const FILTER_FALLBACK_NOTICE: &[u8] = b"\r\n[spawn: output filter stopped, showing raw output]\r\n";
+
+/// How long a filter may take to flush once the session's output has ended before it is killed.
+const FILTER_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Output chunks queued for a filter that isn't keeping up. Once full, the filter is
+/// considered stuck and the session falls back to raw output rather than blocking.
+const FILTER_QUEUE_CHUNKS: usize = 64;
+
+/// `line` run by the platform shell.
+fn shell_command(line: &str) -> std::process::Command {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+    let mut cmd = std::process::Command::new(shell);
+    cmd.args([flag, line]);
+    cmd
+}
+
+/// A session's output passed through a filter command (e.g. `jq` or a colorizer), read back
+/// as the filter prints it. If the filter can't be started, exits or stops reading, the raw
+/// output is passed on from then on, after `FILTER_FALLBACK_NOTICE`. Reaches EOF once the
+/// source has ended and the filter has flushed. `env`, when given, replaces the app's
+/// environment for the filter as it does for the agent.
+struct OutputFilter(ChannelReader);
+
+impl OutputFilter {
+    fn new(
+        mut source: Box<dyn Read + Send>,
+        filter: &str,
+        cwd: &str,
+        env: Option<&BTreeMap<String, String>>,
+    ) -> Self {
+        use std::process::Stdio;
+
+        let (tx, rx) = mpsc::channel();
+        let mut cmd = shell_command(filter);
+        if let Some(env) = env {
+            cmd.env_clear().envs(env);
+        }
+        let spawned = cmd
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => Some(child),
+            Err(e) => {
+                eprintln!("Output filter `{}` failed to start: {}", filter, e);
+                None
+            }
+        };
+        let alive = Arc::new(AtomicBool::new(child.is_some()));
+        // Written from its own thread, so a filter that stops reading can't block the source.
+        let mut input = child.as_mut().and_then(|c| c.stdin.take()).map(|mut stdin| {
+            let (input_tx, input_rx) = mpsc::sync_channel::<Vec<u8>>(FILTER_QUEUE_CHUNKS);
+            std::thread::spawn(move || {
+                for chunk in input_rx {
+                    if stdin.write_all(&chunk).and_then(|_| stdin.flush()).is_err() {
+                        break;
+                    }
+                }
+            });
+            input_tx
+        });
+        if let Some(mut stdout) = child.as_mut().and_then(|c| c.stdout.take()) {
+            let tx = tx.clone();
+            let alive = Arc::clone(&alive);
+            std::thread::spawn(move || {
+                let mut buf = [0u8; 8192];
+                loop {
+                    match stdout.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            if tx.send(buf[..n].to_vec()).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                alive.store(false, Ordering::Relaxed);
+            });
+        }
+
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            let mut raw = false;
+            loop {
+                let n = match source.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                if !raw {
+                    let queued = alive.load(Ordering::Relaxed)
+                        && input.as_ref().is_some_and(|i| i.try_send(buf[..n].to_vec()).is_ok());
+                    if queued {
+                        continue;
+                    }
+                    raw = true;
+                    input = None;
+                    let _ = tx.send(FILTER_FALLBACK_NOTICE.to_vec());
+                }
+                if tx.send(buf[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+            // Once the queue drains, EOF on its stdin lets the filter flush and exit.
+            drop(input);
+            if let Some(mut child) = child {
+                let deadline = Instant::now() + FILTER_DRAIN_TIMEOUT;
+                while matches!(child.try_wait(), Ok(None)) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        });
+        Self(ChannelReader::new(rx))
+    }
+}
+
+impl Read for OutputFilter {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+/// Bytes requested per read from a session's output. Larger buffers mean fewer read calls
+/// for agents that print a lot.
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 8192;
+
+/// A session behind its own lock, so work on one session never blocks the others.
+pub type SharedSession = Arc<Mutex<PtySession>>;
+
 pub struct PtyManager {
-    pub sessions: Arc<Mutex<HashMap<String, PtySession>>>,
+    /// The map lock is only held to look up, insert or remove entries; per-session state is
+    /// guarded by each session's own mutex.
+    pub sessions: Arc<RwLock<HashMap<String, SharedSession>>>,
+    /// Prompt patterns for awaiting-input detection; empty disables it.
+    pub prompt_patterns: Arc<Mutex<Vec<String>>>,
+    /// Inactivity after which a session is marked idle (and killed if opted in); `None` disables.
+    pub idle_timeout: Arc<Mutex<Option<Duration>>>,
+    /// Read buffer size used by reader threads of newly spawned sessions.
+    pub read_buffer_size: Arc<Mutex<usize>>,
+    /// Interval between `session-heartbeat` events; `None` disables them.
+    pub heartbeat_interval: Arc<Mutex<Option<Duration>>>,
+    /// Whether new sessions tee their raw output to a log file under `log_dir`.
+    pub session_logs: Arc<Mutex<bool>>,
+    /// Whether a stopped session's scrollback (and task transcript) is saved to the database.
+    pub persist_scrollback: Arc<Mutex<bool>>,
+    /// Where session logs are written; set once at startup.
+    pub log_dir: Option<PathBuf>,
+    /// Which natural exits emit `session-notification`.
+    pub notification_rules: Arc<Mutex<NotificationRules>>,
+    /// Largest single write accepted by `write_to_session`.
+    pub max_write_bytes: Arc<Mutex<usize>>,
 }
 
 impl PtyManager {
     pub fn new() -> Self {
         Self {
-            sessions: Arc::new(Mutex::new(HashMap::new())),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            prompt_patterns: Arc::new(Mutex::new(Vec::new())),
+            idle_timeout: Arc::new(Mutex::new(None)),
+            read_buffer_size: Arc::new(Mutex::new(DEFAULT_READ_BUFFER_SIZE)),
+            heartbeat_interval: Arc::new(Mutex::new(None)),
+            session_logs: Arc::new(Mutex::new(false)),
+            persist_scrollback: Arc::new(Mutex::new(true)),
+            log_dir: None,
+            notification_rules: Arc::new(Mutex::new(NotificationRules::default())),
+            max_write_bytes: Arc::new(Mutex::new(DEFAULT_MAX_WRITE_BYTES)),
         }
     }
 
+    /// The session's log file, if one has been written.
+    pub fn log_path(&self, id: &str) -> Option<PathBuf> {
+        let path = session_log::log_path(self.log_dir.as_ref()?, id);
+        path.exists().then_some(path)
+    }
+
+    /// Look up a session handle; the map lock is released before the caller locks the session.
+    fn session(&self, id: &str) -> Option<SharedSession> {
+        self.sessions.read().unwrap().get(id).cloned()
+    }
+
+    fn require_session(&self, id: &str) -> Result<SharedSession> {
+        self.session(id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found"))
+    }
+
     pub fn get_session(&self, id: &str) -> Option<(SessionStatus, Vec<u8>)> {
-        let sessions = self.sessions.lock().unwrap();
-        sessions.get(id).map(|s| (s.status.clone(), s.scrollback.clone()))
+        let session = self.session(id)?;
+        let s = session.lock().unwrap();
+        Some((s.status.clone(), s.scrollback.clone()))
     }
 
-    pub fn kill_session(&self, id: &str) {
-        if let Some(mut session) = self.sessions.lock().unwrap().remove(id) {
-            let _ = session.child.kill();
+    /// Scrollback bytes after `offset`, plus the current total length. Only the delta is
+    /// copied. An offset past the end (e.g. a stale client) returns the whole buffer.
+    pub fn get_scrollback_since(&self, id: &str, offset: usize) -> Option<(Vec<u8>, usize)> {
+        let session = self.session(id)?;
+        let s = session.lock().unwrap();
+        Some(scrollback_delta(&s.scrollback, offset))
+    }
+
+    /// Whether any session is still running or idle (i.e. not yet exited).
+    pub fn has_live_sessions(&self) -> bool {
+        let sessions: Vec<SharedSession> = self.sessions.read().unwrap().values().cloned().collect();
+        sessions
+            .iter()
+            .any(|s| !matches!(s.lock().unwrap().status, SessionStatus::Stopped))
+    }
+
+    pub fn get_status(&self, id: &str) -> Option<SessionStatus> {
+        let session = self.session(id)?;
+        let status = session.lock().unwrap().status.clone();
+        Some(status)
+    }
+
+    pub fn terminal_title(&self, id: &str) -> Option<String> {
+        let session = self.session(id)?;
+        let title = session.lock().unwrap().terminal_title.clone();
+        title
+    }
+
+    /// Returns whether the session existed.
+    pub fn kill_session(&self, id: &str) -> bool {
+        let removed = self.sessions.write().unwrap().remove(id);
+        if let Some(session) = &removed {
+            let _ = session.lock().unwrap().child.kill();
+        }
+        removed.is_some()
+    }
+
+    /// Forget a session without killing it. Its reader thread keeps draining output so the
+    /// process doesn't block, but nothing is saved or reaped for it any more, and it is not
+    /// killed on app exit. Returns whether the session existed.
+    pub fn detach_session(&self, id: &str) -> bool {
+        self.sessions.write().unwrap().remove(id).is_some()
+    }
+
+    /// Id, scrollback and counters of every session, e.g. to persist them before shutdown.
+    pub fn scrollbacks(&self) -> Vec<(String, Vec<u8>, SessionCounters)> {
+        let sessions: Vec<SharedSession> = self.sessions.read().unwrap().values().cloned().collect();
+        sessions
+            .iter()
+            .map(|session| {
+                let s = session.lock().unwrap();
+                (s.id.clone(), s.scrollback.clone(), s.counters)
+            })
+            .collect()
+    }
+
+    /// Kill every live PTY child. Called on app exit so agents don't outlive the app.
+    pub fn kill_all(&self) {
+        let drained: Vec<SharedSession> = match self.sessions.write() {
+            Ok(mut map) => map.drain().map(|(_, s)| s).collect(),
+            Err(_) => return,
+        };
+        for session in drained {
+            let mut s = session.lock().unwrap();
+            let _ = s.child.kill();
+            let _ = s.child.wait();
         }
     }
 
+    /// Fails with `SessionExited` rather than a raw broken-pipe error when the child is gone,
+    /// and with `WriteTooLarge` for a write over the configured limit.
     pub fn write_to_session(&self, id: &str, data: &[u8]) -> Result<()> {
-        let mut sessions = self.sessions.lock().unwrap();
-        let session = sessions
-            .get_mut(id)
-            .ok_or_else(|| anyhow::anyhow!("session not found"))?;
-        session.writer.write_all(data)?;
+        let session = self.require_session(id)?;
+        let max = *self.max_write_bytes.lock().unwrap();
+        if data.len() > max {
+            return Err(WriteTooLarge { len: data.len(), max }.into());
+        }
+        let mut s = session.lock().unwrap();
+        if s.has_exited() {
+            return Err(SessionExited.into());
+        }
+        if s.readonly {
+            return Err(SessionReadOnly.into());
+        }
+        if let Err(e) = s.writer.write_all(data) {
+            if e.kind() == std::io::ErrorKind::BrokenPipe || s.has_exited() {
+                return Err(SessionExited.into());
+            }
+            return Err(e.into());
+        }
+        s.counters.bytes_in += data.len() as u64;
+        s.mark_active();
         Ok(())
     }
 
-    pub fn resize_session(&self, id: &str, cols: u16, rows: u16) -> Result<()> {
-        use portable_pty::PtySize;
-        let sessions = self.sessions.lock().unwrap();
-        if let Some(session) = sessions.get(id) {
-            session.master.resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })?;
+    pub fn counters(&self, id: &str) -> Option<SessionCounters> {
+        Some(self.session(id)?.lock().unwrap().counters)
+    }
+
+    /// Flag a running or idle session that hasn't produced any output as `FailedToStart`. With
+    /// `kill` the process is killed too, staying in the map so the reader thread runs the normal
+    /// exit flow; otherwise it is left alone. Returns whether the session was flagged.
+    pub fn mark_failed_to_start(&self, id: &str, kill: bool) -> bool {
+        let Some(session) = self.session(id) else { return false };
+        let mut session = session.lock().unwrap();
+        // The idle reaper may already have moved a silent session to `Idle`.
+        if !matches!(session.status, SessionStatus::Running | SessionStatus::Idle)
+            || session.counters.bytes_out > 0
+        {
+            return false;
+        }
+        session.status = SessionStatus::FailedToStart;
+        if kill {
+            let _ = session.child.kill();
         }
+        true
+    }
+
+    pub fn set_readonly(&self, id: &str, readonly: bool) -> Result<()> {
+        let session = self.require_session(id)?;
+        session.lock().unwrap().readonly = readonly;
+        Ok(())
+    }
+
+    pub fn set_kill_when_idle(&self, id: &str, enabled: bool) -> Result<()> {
+        let session = self.require_session(id)?;
+        session.lock().unwrap().kill_when_idle = enabled;
         Ok(())
     }
 
+    /// Mark sessions inactive for longer than the idle timeout as `Idle`, and kill the ones
+    /// opted into `kill_when_idle`. Killed sessions stay in the map so the reader thread runs
+    /// the normal exit flow. Returns the ids that were killed.
+    pub fn reap_idle_sessions(&self) -> Vec<String> {
+        let Some(timeout) = *self.idle_timeout.lock().unwrap() else {
+            return Vec::new();
+        };
+        let mut killed = Vec::new();
+        let sessions: Vec<SharedSession> = self.sessions.read().unwrap().values().cloned().collect();
+        for session in sessions {
+            let mut session = session.lock().unwrap();
+            if !matches!(session.status, SessionStatus::Running | SessionStatus::Idle)
+                || session.last_activity.elapsed() < timeout
+            {
+                continue;
+            }
+            session.status = SessionStatus::Idle;
+            if session.kill_when_idle && session.child.kill().is_ok() {
+                killed.push(session.id.clone());
+            }
+        }
+        killed
+    }
+
+    /// Byte counts since the previous call for every session that hasn't stopped,
+    /// resetting the counters.
+    pub fn take_heartbeats(&self) -> Vec<Heartbeat> {
+        let sessions: Vec<SharedSession> = self.sessions.read().unwrap().values().cloned().collect();
+        sessions
+            .iter()
+            .filter_map(|session| {
+                let mut s = session.lock().unwrap();
+                if matches!(s.status, SessionStatus::Stopped) {
+                    return None;
+                }
+                let bytes = std::mem::take(&mut s.bytes_since_heartbeat);
+                Some(Heartbeat { session_id: s.id.clone(), bytes })
+            })
+            .collect()
+    }
+
+    /// Sessions whose sentinel file has appeared since the last call. The file is removed so
+    /// the agent can signal again for its next task; anything but a regular file is ignored.
+    pub fn take_sentinel_completions(&self) -> Vec<String> {
+        let sessions: Vec<SharedSession> = self.sessions.read().unwrap().values().cloned().collect();
+        sessions
+            .iter()
+            .filter_map(|session| {
+                let s = session.lock().unwrap();
+                let path = s.sentinel_file.as_ref()?;
+                if matches!(s.status, SessionStatus::Stopped) || !path.is_file() {
+                    return None;
+                }
+                let _ = std::fs::remove_file(path);
+                Some(s.id.clone())
+            })
+            .collect()
+    }
+
+    /// Resize the session's PTY. Returns whether a resize was applied: bogus sizes, unchanged
+    /// sizes (which would only cause a spurious SIGWINCH) and piped sessions are skipped.
+    pub fn resize_session(&self, id: &str, cols: u16, rows: u16) -> Result<bool> {
+        use portable_pty::PtySize;
+        let Some((cols, rows)) = clamp_size(cols, rows) else {
+            return Ok(false);
+        };
+        let Some(session) = self.session(id) else {
+            return Ok(false);
+        };
+        let session = session.lock().unwrap();
+        let Some(master) = &session.master else {
+            return Ok(false);
+        };
+        if master
+            .get_size()
+            .is_ok_and(|size| size.cols == cols && size.rows == rows)
+        {
+            return Ok(false);
+        }
+        master.resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        Ok(true)
+    }
+
+    fn insert_session(&self, session: PtySession) -> SharedSession {
+        let shared = Arc::new(Mutex::new(session));
+        let id = shared.lock().unwrap().id.clone();
+        self.sessions.write().unwrap().insert(id, Arc::clone(&shared));
+        shared
+    }
+
+    /// Forward PTY output to the broadcast channel and scrollback until EOF, then mark the
+    /// session stopped and emit `session-exited` unless it was killed explicitly, plus
+    /// `session-notification` when the notification rules allow it.
+    /// The reader holds its own session handle, so appending never touches the map lock.
+    fn spawn_reader(
+        &self,
+        session: SharedSession,
+        mut reader: Box<dyn Read + Send>,
+        output_tx: tokio::sync::broadcast::Sender<(String, Vec<u8>)>,
+        app: tauri::AppHandle,
+    ) {
+        let (sid, marker) = {
+            let s = session.lock().unwrap();
+            (s.id.clone(), s.completion_marker.clone())
+        };
+        let sessions_arc = Arc::clone(&self.sessions);
+        let prompt_patterns = Arc::clone(&self.prompt_patterns);
+        let notification_rules = Arc::clone(&self.notification_rules);
+        let persist_scrollback = Arc::clone(&self.persist_scrollback);
+        let buf_size = *self.read_buffer_size.lock().unwrap();
+        let mut log = match &self.log_dir {
+            Some(dir) if *self.session_logs.lock().unwrap() => SessionLog::open(dir, &sid)
+                .map_err(|e| eprintln!("Failed to open session log for {}: {}", sid, e))
+                .ok(),
+            _ => None,
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let mut buf = vec![0u8; buf_size];
+            let mut prompt = PromptDetector::default();
+            let mut titles = TitleScanner::default();
+            let mut completion = marker.as_deref().and_then(MarkerScanner::new);
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let data = buf[..n].to_vec();
+                        let _ = output_tx.send((sid.clone(), data.clone()));
+                        if let Some(log) = log.as_mut() {
+                            let _ = log.write(&data);
+                        }
+                        let mut title = titles.feed(&data);
+                        if let Ok(mut s) = session.lock() {
+                            s.scrollback.extend_from_slice(&data);
+                            s.bytes_since_heartbeat += n as u64;
+                            s.counters.bytes_out += n as u64;
+                            s.mark_active();
+                            if title.is_some() && title != s.terminal_title {
+                                s.terminal_title = title.clone();
+                            } else {
+                                title = None;
+                            }
+                        }
+                        let completed = completion.as_mut().map_or(0, |c| c.feed(&data));
+                        for _ in 0..completed {
+                            let _ = app.emit(
+                                "session-task-complete",
+                                TaskComplete { session_id: sid.clone(), source: "marker" },
+                            );
+                        }
+                        if let Some(title) = title {
+                            let _ = app.emit(
+                                "session-title-changed",
+                                TitleChanged { session_id: sid.clone(), title },
+                            );
+                        }
+                        let awaiting = prompt_patterns
+                            .lock()
+                            .ok()
+                            .and_then(|patterns| prompt.feed(&data, &patterns));
+                        if let Some(prompt) = awaiting {
+                            let _ = app.emit(
+                                "session-awaiting-input",
+                                AwaitingInput { session_id: sid.clone(), prompt },
+                            );
+                        }
+                    }
+                }
+            }
+            // Explicitly killed sessions have already been removed from the map.
+            let natural_exit = sessions_arc
+                .read()
+                .map(|map| map.contains_key(&sid))
+                .unwrap_or(false);
+            let mut notification = None;
+            if natural_exit {
+                let exit_code = wait_exit_code(&session);
+                let (scrollback, counters) = match session.lock() {
+                    Ok(mut s) => {
+                        s.status = SessionStatus::Stopped;
+                        let notify = notification_rules
+                            .lock()
+                            .unwrap()
+                            .should_notify(s.kind, &s.project_id, exit_code);
+                        notification = notify.then(|| SessionNotification {
+                            session_id: sid.clone(),
+                            project_id: s.project_id.clone(),
+                            kind: s.kind,
+                            exit_code,
+                        });
+                        (s.scrollback.clone(), s.counters)
+                    }
+                    Err(_) => (Vec::new(), SessionCounters::default()),
+                };
+                let db = app.state::<crate::AppState>().db();
+                let persist = *persist_scrollback.lock().unwrap();
+                tauri::async_runtime::block_on(async {
+                    let saved = persist.then_some(scrollback.as_slice());
+                    if let Err(e) =
+                        crate::sessions::store_stopped_session_db(&db, &sid, saved, counters).await
+                    {
+                        eprintln!("Failed to save scrollback for {}: {}", sid, e);
+                    }
+                    if !persist {
+                        return;
+                    }
+                    if let Err(e) =
+                        crate::tasks::record_session_transcript_db(&db, &sid, &scrollback).await
+                    {
+                        eprintln!("Failed to record transcript for {}: {}", sid, e);
+                    }
+                });
+            }
+            if natural_exit {
+                let _ = app.emit("session-exited", sid.clone());
+            }
+            if let Some(notification) = notification {
+                let _ = app.emit("session-notification", notification);
+            }
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn_agent(
         &self,
         session_id: String,
@@ -74,6 +880,9 @@ impl PtyManager {
         project_path: &str,
         command: &str,
         args: &[String],
+        env: Option<&BTreeMap<String, String>>,
+        output_filter: Option<&str>,
+        completion: CompletionSignal,
         output_tx: tokio::sync::broadcast::Sender<(String, Vec<u8>)>,
         app_handle: tauri::AppHandle,
     ) -> Result<String> {
@@ -92,57 +901,109 @@ impl PtyManager {
             cmd.arg(arg);
         }
         cmd.cwd(project_path);
+        if let Some(env) = env {
+            cmd.env_clear();
+            for (key, value) in env {
+                cmd.env(key, value);
+            }
+        }
 
         let child = pair.slave.spawn_command(cmd)?;
         let writer = pair.master.take_writer()?;
-        let mut reader = pair.master.try_clone_reader()?;
-
-        let sid = session_id.clone();
-        let sessions_arc = Arc::clone(&self.sessions);
-        let app = app_handle.clone();
-
-        tokio::task::spawn_blocking(move || {
-            let mut buf = [0u8; 1024];
-            loop {
-                match reader.read(&mut buf) {
-                    Ok(0) | Err(_) => break,
-                    Ok(n) => {
-                        let data = buf[..n].to_vec();
-                        let _ = output_tx.send((sid.clone(), data.clone()));
-                        if let Ok(mut map) = sessions_arc.lock() {
-                            if let Some(s) = map.get_mut(&sid) {
-                                s.scrollback.extend_from_slice(&data);
-                            }
-                        }
-                    }
-                }
-            }
-            let natural_exit = if let Ok(mut map) = sessions_arc.lock() {
-                if let Some(s) = map.get_mut(&sid) {
-                    s.status = SessionStatus::Stopped;
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            };
-            if natural_exit {
-                let _ = app.emit("session-exited", sid.clone());
-            }
-        });
+        let reader = pair.master.try_clone_reader()?;
 
         let session = PtySession {
             id: session_id.clone(),
             project_id,
+            kind: SessionKind::Agent,
             status: SessionStatus::Running,
             scrollback: Vec::new(),
             writer,
             child,
-            master: pair.master,
+            master: Some(pair.master),
+            last_activity: Instant::now(),
+            bytes_since_heartbeat: 0,
+            counters: SessionCounters::default(),
+            terminal_title: None,
+            completion_marker: completion.marker,
+            sentinel_file: completion
+                .sentinel_file
+                .map(|f| std::path::Path::new(project_path).join(f)),
+            kill_when_idle: false,
+            readonly: false,
+        };
+
+        let reader: Box<dyn Read + Send> = match output_filter {
+            Some(filter) => Box::new(OutputFilter::new(reader, filter, project_path, env)),
+            None => reader,
+        };
+        let shared = self.insert_session(session);
+        self.spawn_reader(shared, reader, output_tx, app_handle);
+        Ok(session_id)
+    }
+
+    /// Like `spawn_agent`, but over plain stdin/stdout/stderr pipes instead of a PTY, for
+    /// batch agents where terminal echo and control sequences are just noise. Output from both
+    /// streams is merged line by line into the same scrollback and broadcast channel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn_agent_piped(
+        &self,
+        session_id: String,
+        project_id: String,
+        project_path: &str,
+        command: &str,
+        args: &[String],
+        env: Option<&BTreeMap<String, String>>,
+        output_filter: Option<&str>,
+        completion: CompletionSignal,
+        output_tx: tokio::sync::broadcast::Sender<(String, Vec<u8>)>,
+        app_handle: tauri::AppHandle,
+    ) -> Result<String> {
+        use std::process::{Command, Stdio};
+
+        let mut cmd = Command::new(command);
+        cmd.args(args).current_dir(project_path);
+        if let Some(env) = env {
+            cmd.env_clear().envs(env);
+        }
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let writer = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        let session = PtySession {
+            id: session_id.clone(),
+            project_id,
+            kind: SessionKind::Agent,
+            status: SessionStatus::Running,
+            scrollback: Vec::new(),
+            writer: Box::new(writer),
+            child: Box::new(child),
+            master: None,
+            last_activity: Instant::now(),
+            bytes_since_heartbeat: 0,
+            counters: SessionCounters::default(),
+            terminal_title: None,
+            completion_marker: completion.marker,
+            sentinel_file: completion
+                .sentinel_file
+                .map(|f| std::path::Path::new(project_path).join(f)),
+            kill_when_idle: false,
+            readonly: false,
         };
 
-        self.sessions.lock().unwrap().insert(session_id.clone(), session);
+        let shared = self.insert_session(session);
+        let reader: Box<dyn Read + Send> =
+            Box::new(LineMerger::new(vec![Box::new(stdout), Box::new(stderr)]));
+        let reader: Box<dyn Read + Send> = match output_filter {
+            Some(filter) => Box::new(OutputFilter::new(reader, filter, project_path, env)),
+            None => reader,
+        };
+        self.spawn_reader(shared, reader, output_tx, app_handle);
         Ok(session_id)
     }
 
@@ -150,6 +1011,7 @@ impl PtyManager {
         &self,
         session_id: String,
         cwd: &str,
+        kill_when_idle: bool,
         output_tx: tokio::sync::broadcast::Sender<(String, Vec<u8>)>,
         app_handle: tauri::AppHandle,
     ) -> Result<String> {
@@ -170,54 +1032,29 @@ impl PtyManager {
 
         let child = pair.slave.spawn_command(cmd)?;
         let writer = pair.master.take_writer()?;
-        let mut reader = pair.master.try_clone_reader()?;
-
-        let sid = session_id.clone();
-        let sessions_arc = Arc::clone(&self.sessions);
-        let app = app_handle.clone();
-
-        tokio::task::spawn_blocking(move || {
-            let mut buf = [0u8; 1024];
-            loop {
-                match reader.read(&mut buf) {
-                    Ok(0) | Err(_) => break,
-                    Ok(n) => {
-                        let data = buf[..n].to_vec();
-                        let _ = output_tx.send((sid.clone(), data.clone()));
-                        if let Ok(mut map) = sessions_arc.lock() {
-                            if let Some(s) = map.get_mut(&sid) {
-                                s.scrollback.extend_from_slice(&data);
-                            }
-                        }
-                    }
-                }
-            }
-            let natural_exit = if let Ok(mut map) = sessions_arc.lock() {
-                if let Some(s) = map.get_mut(&sid) {
-                    s.status = SessionStatus::Stopped;
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            };
-            if natural_exit {
-                let _ = app.emit("session-exited", sid.clone());
-            }
-        });
+        let reader = pair.master.try_clone_reader()?;
 
         let session = PtySession {
             id: session_id.clone(),
             project_id: String::new(),
+            kind: SessionKind::Shell,
             status: SessionStatus::Running,
             scrollback: Vec::new(),
             writer,
             child,
-            master: pair.master,
+            master: Some(pair.master),
+            last_activity: Instant::now(),
+            bytes_since_heartbeat: 0,
+            counters: SessionCounters::default(),
+            terminal_title: None,
+            completion_marker: None,
+            sentinel_file: None,
+            kill_when_idle,
+            readonly: false,
         };
 
-        self.sessions.lock().unwrap().insert(session_id.clone(), session);
+        let shared = self.insert_session(session);
+        self.spawn_reader(shared, reader, output_tx, app_handle);
         Ok(session_id)
     }
 }
@@ -228,6 +1065,96 @@ impl Default for PtyManager {
     }
 }
 
+/// Output kept from a trial run; enough to show a banner or an error message.
+const TRIAL_OUTPUT_BYTES: usize = 4096;
+
+/// What happened when an agent command was launched briefly by `trial_run`.
+#[derive(Debug, Serialize)]
+pub struct TrialRun {
+    /// Spawned, and either still running at the deadline or exited with status 0.
+    pub started: bool,
+    /// Set when the process exited before the deadline.
+    pub exit_code: Option<u32>,
+    /// The start of its output, escape sequences removed.
+    pub output: String,
+    /// Why the command couldn't be spawned at all (e.g. not found).
+    pub error: Option<String>,
+}
+
+/// Launch `command` in a PTY inside an empty temp directory, watch it for up to `timeout`,
+/// then kill it. No session is created and the temp directory is removed afterwards.
+pub fn trial_run(command: &str, args: &[String], timeout: Duration) -> Result<TrialRun> {
+    use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+
+    let cwd = std::env::temp_dir().join(format!("spawn-trial-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&cwd)?;
+    let pair = NativePtySystem::default()
+        .openpty(PtySize { rows: 24, cols: 80, pixel_width: 0, pixel_height: 0 })?;
+    let mut cmd = CommandBuilder::new(command);
+    cmd.args(args);
+    cmd.cwd(&cwd);
+    let mut child = match pair.slave.spawn_command(cmd) {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&cwd);
+            return Ok(TrialRun {
+                started: false,
+                exit_code: None,
+                output: String::new(),
+                error: Some(e.to_string()),
+            });
+        }
+    };
+    drop(pair.slave);
+
+    let mut reader = pair.master.try_clone_reader()?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 1024];
+        while let Ok(n) = reader.read(&mut buf) {
+            if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut output = Vec::new();
+    let mut exit_code = None;
+    loop {
+        if let Ok(Some(status)) = child.try_wait() {
+            exit_code = Some(status.exit_code());
+            // Pick up whatever it printed on the way out.
+            while let Ok(chunk) = rx.recv_timeout(Duration::from_millis(100)) {
+                output.extend_from_slice(&chunk);
+            }
+            break;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        if let Ok(chunk) = rx.recv_timeout((deadline - now).min(Duration::from_millis(50))) {
+            output.extend_from_slice(&chunk);
+        }
+    }
+    if exit_code.is_none() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    // Closing the master hangs up the terminal, so anything the command started goes too.
+    drop(pair.master);
+    let _ = std::fs::remove_dir_all(&cwd);
+
+    output.truncate(TRIAL_OUTPUT_BYTES);
+    Ok(TrialRun {
+        started: exit_code.is_none_or(|code| code == 0),
+        exit_code,
+        output: crate::ansi::strip_ansi(&String::from_utf8_lossy(&output)).trim().to_string(),
+        error: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,7 +1162,7 @@ mod tests {
     #[test]
     fn test_new_manager_has_no_sessions() {
         let manager = PtyManager::new();
-        assert_eq!(manager.sessions.lock().unwrap().len(), 0);
+        assert_eq!(manager.sessions.read().unwrap().len(), 0);
     }
 
     #[test]
@@ -245,11 +1172,470 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_isolated_env_keeps_only_the_safe_set() {
+        let env = isolated_env();
+        assert!(env.keys().all(|k| ISOLATED_ENV_VARS.contains(&k.as_str())));
+        assert!(env.contains_key("TERM"));
+        assert_eq!(env.get("PATH"), std::env::var("PATH").ok().as_ref());
+    }
+
+    #[test]
+    fn test_status_as_str_matches_db_values() {
+        assert_eq!(SessionStatus::Running.as_str(), "running");
+        assert_eq!(SessionStatus::Idle.as_str(), "idle");
+        assert_eq!(SessionStatus::Stopped.as_str(), "stopped");
+        assert_eq!(SessionStatus::FailedToStart.as_str(), "failed_to_start");
+    }
+
+    #[test]
+    fn test_scrollback_delta() {
+        assert_eq!(scrollback_delta(b"hello world", 6), (b"world".to_vec(), 11));
+        assert_eq!(scrollback_delta(b"hello", 5), (Vec::new(), 5));
+        assert_eq!(scrollback_delta(b"hello", 99), (b"hello".to_vec(), 5));
+    }
+
+    #[test]
+    fn test_line_merger_keeps_lines_whole_and_ends_after_all_streams() {
+        let out: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(b"out 1\nout 2\n".to_vec()));
+        let err: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(b"err 1\nerr tail".to_vec()));
+        let mut merged = String::new();
+        LineMerger::new(vec![out, err]).read_to_string(&mut merged).unwrap();
+
+        let mut lines: Vec<&str> = merged.split_inclusive('\n').collect();
+        lines.sort();
+        assert_eq!(lines, ["err 1\n", "err tail\n", "out 1\n", "out 2\n"]);
+    }
+
+    /// Yields each chunk after a pause, like an agent printing now and then.
+    struct SlowSource(std::collections::VecDeque<&'static [u8]>);
+
+    impl Read for SlowSource {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            std::thread::sleep(Duration::from_millis(200));
+            let Some(chunk) = self.0.pop_front() else { return Ok(0) };
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn test_output_filter_transforms_output() {
+        let source: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(b"hello\nworld\n".to_vec()));
+        let mut out = String::new();
+        OutputFilter::new(source, "tr a-z A-Z", "/tmp", None).read_to_string(&mut out).unwrap();
+        assert_eq!(out, "HELLO\nWORLD\n");
+    }
+
+    #[test]
+    fn test_output_filter_falls_back_to_raw_output() {
+        let source = Box::new(SlowSource([&b"abc"[..], b"def"].into()));
+        let mut out = Vec::new();
+        OutputFilter::new(source, "exit 3", "/tmp", None).read_to_end(&mut out).unwrap();
+        assert_eq!(out, [FILTER_FALLBACK_NOTICE, b"abcdef"].concat());
+
+        let source = Box::new(SlowSource([&b"abc"[..]].into()));
+        let mut out = Vec::new();
+        OutputFilter::new(source, "true", "/nonexistent-dir", None).read_to_end(&mut out).unwrap();
+        assert_eq!(out, [FILTER_FALLBACK_NOTICE, b"abc"].concat());
+    }
+
+    #[test]
+    fn test_output_filter_falls_back_when_filter_stops_reading() {
+        // More than the queue and a pipe buffer can hold, to a filter that never reads.
+        let chunk = vec![b'x'; 8192];
+        let total = (FILTER_QUEUE_CHUNKS + 64) * chunk.len();
+        let source: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(vec![b'x'; total]));
+        let mut out = Vec::new();
+        OutputFilter::new(source, "exec sleep 5", "/tmp", None).read_to_end(&mut out).unwrap();
+        assert!(out.windows(FILTER_FALLBACK_NOTICE.len()).any(|w| w == FILTER_FALLBACK_NOTICE));
+    }
+
+    #[test]
+    fn test_output_filter_uses_given_env() {
+        let source: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(Vec::new()));
+        let env = BTreeMap::from([("ONLY_VAR".to_string(), "set".to_string())]);
+        let mut out = String::new();
+        OutputFilter::new(source, "echo \"$ONLY_VAR${HOME:-}\"", "/tmp", Some(&env))
+            .read_to_string(&mut out)
+            .unwrap();
+        assert_eq!(out, "set\n");
+    }
+
+    #[test]
+    fn test_resize_clamps_and_skips_unchanged() {
+        assert_eq!(clamp_size(0, 24), None);
+        assert_eq!(clamp_size(80, 0), None);
+        assert_eq!(clamp_size(u16::MAX, 30), Some((MAX_COLS, 30)));
+
+        let manager = PtyManager::new();
+        let shared = manager.insert_session(bench_session("s1", 0));
+        assert!(!manager.resize_session("s1", 80, 24).unwrap());
+        assert!(!manager.resize_session("s1", 0, 0).unwrap());
+        assert!(manager.resize_session("s1", 120, 40).unwrap());
+        assert!(!manager.resize_session("s1", 120, 40).unwrap());
+        assert!(!manager.resize_session("missing", 120, 40).unwrap());
+        let _ = shared.lock().unwrap().child.kill();
+    }
+
+    #[test]
+    fn test_trial_run_reports_exit_and_output() {
+        let quick = trial_run("sh", &["-c".into(), "echo hello; exit 3".into()], Duration::from_secs(5)).unwrap();
+        assert!(!quick.started);
+        assert_eq!(quick.exit_code, Some(3));
+        assert_eq!(quick.output, "hello");
+
+        let long = trial_run("sleep", &["30".into()], Duration::from_millis(200)).unwrap();
+        assert!(long.started);
+        assert_eq!(long.exit_code, None);
+
+        let missing = trial_run("spawn-no-such-command", &[], Duration::from_secs(1)).unwrap();
+        assert!(!missing.started);
+        assert!(missing.error.is_some() || missing.exit_code.is_some());
+    }
+
+    #[test]
+    fn test_write_counts_input_bytes() {
+        let manager = PtyManager::new();
+        let shared = manager.insert_session(bench_session("s1", 0));
+        manager.write_to_session("s1", b"hello\n").unwrap();
+        manager.write_to_session("s1", b"!").unwrap();
+        assert_eq!(manager.counters("s1"), Some(SessionCounters { bytes_out: 0, bytes_in: 7 }));
+        assert_eq!(manager.counters("missing"), None);
+        let _ = shared.lock().unwrap().child.kill();
+    }
+
+    #[test]
+    fn test_readonly_session_rejects_writes_until_cleared() {
+        let manager = PtyManager::new();
+        let session = manager.insert_session(bench_session("s1", 0));
+        manager.set_readonly("s1", true).unwrap();
+        let err = manager.write_to_session("s1", b"hi").unwrap_err();
+        assert!(err.downcast_ref::<SessionReadOnly>().is_some());
+        assert_eq!(session.lock().unwrap().counters.bytes_in, 0);
+
+        manager.set_readonly("s1", false).unwrap();
+        manager.write_to_session("s1", b"hi").unwrap();
+        assert!(manager.set_readonly("missing", true).is_err());
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_write_rejects_oversized_and_unknown() {
+        let manager = PtyManager::new();
+        let shared = manager.insert_session(bench_session("s1", 0));
+        *manager.max_write_bytes.lock().unwrap() = 4;
+        manager.write_to_session("s1", b"four").unwrap();
+        let err = manager.write_to_session("s1", b"fives").unwrap_err();
+        assert!(err.downcast_ref::<WriteTooLarge>().is_some());
+        assert_eq!(manager.counters("s1").unwrap().bytes_in, 4);
+        let err = manager.write_to_session("missing", b"x").unwrap_err();
+        assert_eq!(err.to_string(), "Session not found");
+        let _ = shared.lock().unwrap().child.kill();
+    }
+
+    #[test]
+    fn test_marker_scanner_counts_split_markers_once() {
+        let mut scanner = MarkerScanner::new("<<DONE>>").unwrap();
+        assert_eq!(scanner.feed(b"working... <<DO"), 0);
+        assert_eq!(scanner.feed(b"NE>> next <<DONE>><<DONE>>"), 3);
+        assert_eq!(scanner.feed(b">>"), 0);
+        assert!(MarkerScanner::new("").is_none());
+    }
+
+    #[test]
+    fn test_sentinel_completion_fires_once_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PtyManager::new();
+        let mut session = bench_session("s1", 0);
+        session.sentinel_file = Some(dir.path().join(".spawn/done"));
+        let shared = manager.insert_session(session);
+        assert!(manager.take_sentinel_completions().is_empty());
+
+        std::fs::create_dir_all(dir.path().join(".spawn")).unwrap();
+        std::fs::write(dir.path().join(".spawn/done"), "").unwrap();
+        assert_eq!(manager.take_sentinel_completions(), ["s1"]);
+        assert!(manager.take_sentinel_completions().is_empty());
+
+        std::fs::create_dir(dir.path().join(".spawn/done")).unwrap();
+        assert!(manager.take_sentinel_completions().is_empty());
+        let _ = shared.lock().unwrap().child.kill();
+    }
+
+    #[test]
+    fn test_kill_all_on_empty_manager_is_noop() {
+        let manager = PtyManager::new();
+        manager.kill_all();
+        assert_eq!(manager.sessions.read().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_reap_without_timeout_is_noop() {
+        let manager = PtyManager::new();
+        assert!(manager.reap_idle_sessions().is_empty());
+        *manager.idle_timeout.lock().unwrap() = Some(Duration::from_secs(1));
+        assert!(manager.reap_idle_sessions().is_empty());
+    }
+
+    #[test]
+    fn test_reap_marks_stale_sessions_idle_and_kills_opted_in() {
+        let manager = PtyManager::new();
+        *manager.idle_timeout.lock().unwrap() = Some(Duration::from_secs(60));
+        let stale = Instant::now() - Duration::from_secs(120);
+        let kept = manager.insert_session(bench_session("kept", 0));
+        let killed = manager.insert_session(bench_session("killed", 0));
+        let active = manager.insert_session(bench_session("active", 0));
+        kept.lock().unwrap().last_activity = stale;
+        killed.lock().unwrap().last_activity = stale;
+        killed.lock().unwrap().kill_when_idle = true;
+
+        assert_eq!(manager.reap_idle_sessions(), ["killed"]);
+        assert_eq!(manager.get_status("kept").unwrap().as_str(), "idle");
+        assert_eq!(manager.get_status("active").unwrap().as_str(), "running");
+        assert!(matches!(kept.lock().unwrap().child.try_wait(), Ok(None)));
+        assert!(killed.lock().unwrap().child.wait().is_ok());
+        assert!(matches!(active.lock().unwrap().child.try_wait(), Ok(None)));
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_set_kill_when_idle_on_missing_session_errors() {
+        let manager = PtyManager::new();
+        assert!(manager.set_kill_when_idle("nonexistent", true).is_err());
+    }
+
+    #[test]
+    fn test_write_to_exited_session_reports_session_exited() {
+        let manager = PtyManager::new();
+        let shared = manager.insert_session(bench_session("s1", 0));
+        {
+            let mut s = shared.lock().unwrap();
+            s.child.kill().unwrap();
+            s.child.wait().unwrap();
+        }
+        let err = manager.write_to_session("s1", b"hi\n").unwrap_err();
+        assert!(err.downcast_ref::<SessionExited>().is_some());
+    }
+
+    #[test]
+    fn test_take_heartbeats_resets_and_skips_stopped() {
+        let manager = PtyManager::new();
+        let live = manager.insert_session(bench_session("live", 0));
+        let stopped = manager.insert_session(bench_session("stopped", 0));
+        live.lock().unwrap().bytes_since_heartbeat = 42;
+        stopped.lock().unwrap().status = SessionStatus::Stopped;
+
+        let beats = manager.take_heartbeats();
+        assert_eq!(beats.len(), 1);
+        assert_eq!((beats[0].session_id.as_str(), beats[0].bytes), ("live", 42));
+        assert_eq!(manager.take_heartbeats()[0].bytes, 0);
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_mark_failed_to_start_only_when_silent() {
+        let manager = PtyManager::new();
+        let silent = manager.insert_session(bench_session("silent", 0));
+        let chatty = manager.insert_session(bench_session("chatty", 0));
+        chatty.lock().unwrap().counters.bytes_out = 10;
+
+        assert!(manager.mark_failed_to_start("silent", false));
+        assert!(!manager.mark_failed_to_start("chatty", true));
+        assert!(!manager.mark_failed_to_start("missing", false));
+        assert_eq!(manager.get_status("silent").unwrap().as_str(), "failed_to_start");
+
+        // Late output means it did start after all.
+        silent.lock().unwrap().mark_active();
+        assert_eq!(manager.get_status("silent").unwrap().as_str(), "running");
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_mark_failed_to_start_after_idle_reaper() {
+        let manager = PtyManager::new();
+        let session = manager.insert_session(bench_session("silent", 0));
+        session.lock().unwrap().status = SessionStatus::Idle;
+
+        assert!(manager.mark_failed_to_start("silent", false));
+        assert_eq!(manager.get_status("silent").unwrap().as_str(), "failed_to_start");
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_mark_failed_to_start_can_kill() {
+        let manager = PtyManager::new();
+        let session = manager.insert_session(bench_session("silent", 0));
+
+        assert!(manager.mark_failed_to_start("silent", true));
+        let mut s = session.lock().unwrap();
+        assert!(s.child.wait().is_ok());
+        drop(s);
+        assert!(manager.get_session("silent").is_some());
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_wait_exit_code_does_not_hold_the_lock() {
+        let manager = PtyManager::new();
+        let session = manager.insert_session(bench_session("s1", 0));
+        let waiter = {
+            let session = Arc::clone(&session);
+            std::thread::spawn(move || wait_exit_code(&session))
+        };
+        // The still-running child keeps the waiter polling; the session stays usable.
+        std::thread::sleep(Duration::from_millis(60));
+        session.lock().unwrap().child.kill().unwrap();
+        assert!(waiter.join().unwrap().is_some());
+        manager.kill_all();
+    }
+
+    #[test]
+    fn test_detach_leaves_process_running() {
+        let manager = PtyManager::new();
+        let session = manager.insert_session(bench_session("s1", 0));
+        assert!(manager.detach_session("s1"));
+        assert!(!manager.detach_session("s1"));
+        assert!(manager.get_session("s1").is_none());
+
+        manager.kill_all();
+        let mut s = session.lock().unwrap();
+        assert!(matches!(s.child.try_wait(), Ok(None)));
+        s.child.kill().unwrap();
+    }
+
     #[test]
     fn test_kill_nonexistent_session_is_noop() {
         let manager = PtyManager::new();
         // Should not panic
-        manager.kill_session("nonexistent");
-        assert_eq!(manager.sessions.lock().unwrap().len(), 0);
+        assert!(!manager.kill_session("nonexistent"));
+        assert_eq!(manager.sessions.read().unwrap().len(), 0);
+    }
+
+    /// A real PTY running `sleep` with a pre-filled scrollback.
+    fn bench_session(id: &str, scrollback_len: usize) -> PtySession {
+        use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+        let pair = NativePtySystem::default()
+            .openpty(PtySize { rows: 24, cols: 80, pixel_width: 0, pixel_height: 0 })
+            .unwrap();
+        let mut cmd = CommandBuilder::new("sleep");
+        cmd.arg("30");
+        let child = pair.slave.spawn_command(cmd).unwrap();
+        PtySession {
+            id: id.to_string(),
+            project_id: String::new(),
+            kind: SessionKind::Agent,
+            status: SessionStatus::Running,
+            scrollback: vec![b'x'; scrollback_len],
+            writer: pair.master.take_writer().unwrap(),
+            child,
+            master: Some(pair.master),
+            last_activity: Instant::now(),
+            bytes_since_heartbeat: 0,
+            counters: SessionCounters::default(),
+            terminal_title: None,
+            completion_marker: None,
+            sentinel_file: None,
+            kill_when_idle: false,
+            readonly: false,
+        }
+    }
+
+    /// Read all output of a big-output command through a real PTY; returns (reads, bytes).
+    fn count_pty_reads(buf_size: usize) -> (usize, usize) {
+        use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+        let pair = NativePtySystem::default()
+            .openpty(PtySize { rows: 24, cols: 80, pixel_width: 0, pixel_height: 0 })
+            .unwrap();
+        let mut cmd = CommandBuilder::new("sh");
+        cmd.args(["-c", "head -c 20000000 /dev/zero | tr '\\0' x"]);
+        let mut child = pair.slave.spawn_command(cmd).unwrap();
+        drop(pair.slave);
+        let mut reader = pair.master.try_clone_reader().unwrap();
+        let mut buf = vec![0u8; buf_size];
+        let (mut reads, mut bytes) = (0, 0);
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    reads += 1;
+                    bytes += n;
+                }
+            }
+        }
+        child.wait().unwrap();
+        (reads, bytes)
+    }
+
+    /// Run with `cargo test --release bench_ -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_read_buffer_sizes() {
+        for size in [1024, DEFAULT_READ_BUFFER_SIZE] {
+            let start = Instant::now();
+            let (reads, bytes) = count_pty_reads(size);
+            println!(
+                "buffer {:>5}: {:>6} reads for {} bytes ({:.0} bytes/read) in {:?}",
+                size,
+                reads,
+                bytes,
+                bytes as f64 / reads as f64,
+                start.elapsed()
+            );
+        }
+    }
+
+    /// Run with `cargo test --release bench_ -- --ignored --nocapture`.
+    /// Readers clone large scrollbacks of sessions s1..s7 while the main thread times status
+    /// lookups on s0, which should not have to wait behind other sessions' clones.
+    #[test]
+    #[ignore]
+    fn bench_concurrent_get_scrollback() {
+        const SESSIONS: usize = 8;
+        const THREADS: usize = 7;
+        const CALLS: usize = 50;
+        const SCROLLBACK: usize = 4 * 1024 * 1024;
+
+        let manager = Arc::new(PtyManager::new());
+        for i in 0..SESSIONS {
+            manager.insert_session(bench_session(&format!("s{}", i), SCROLLBACK));
+        }
+
+        let start = Instant::now();
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let manager = Arc::clone(&manager);
+                std::thread::spawn(move || {
+                    for _ in 0..CALLS {
+                        let (_, scrollback) = manager.get_session(&format!("s{}", t + 1)).unwrap();
+                        assert_eq!(scrollback.len(), SCROLLBACK);
+                    }
+                })
+            })
+            .collect();
+        let mut lookups = 0u32;
+        let mut worst = Duration::ZERO;
+        let mut total = Duration::ZERO;
+        while !handles.iter().all(|h| h.is_finished()) {
+            let t = Instant::now();
+            manager.get_status("s0").unwrap();
+            let elapsed = t.elapsed();
+            worst = worst.max(elapsed);
+            total += elapsed;
+            lookups += 1;
+            std::thread::yield_now();
+        }
+        for h in handles {
+            h.join().unwrap();
+        }
+        eprintln!(
+            "{} get_session calls ({} MiB scrollback): {:?}; s0 status lookups: {} avg {:?} worst {:?}",
+            THREADS * CALLS,
+            SCROLLBACK / (1024 * 1024),
+            start.elapsed(),
+            lookups,
+            total / lookups.max(1),
+            worst
+        );
+        manager.kill_all();
     }
 }
@@ -0,0 +1,68 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Once the active log passes this size it is rotated to `<id>.log.1`, so each session
+/// uses at most twice this on disk.
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+pub fn log_path(dir: &Path, session_id: &str) -> PathBuf {
+    dir.join(format!("{}.log", session_id))
+}
+
+/// Raw session output teed to `<dir>/<session_id>.log`, with a single rotated backup.
+pub struct SessionLog {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl SessionLog {
+    pub fn open(dir: &Path, session_id: &str) -> std::io::Result<Self> {
+        Self::with_limit(dir, session_id, MAX_LOG_BYTES)
+    }
+
+    fn with_limit(dir: &Path, session_id: &str, max_bytes: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = log_path(dir, session_id);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self { path, file, written, max_bytes })
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.written > 0 && self.written + data.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(data)?;
+        self.written += data.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        std::fs::rename(&self.path, self.path.with_extension("log.1"))?;
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_log_rotates_past_limit() {
+        let dir = tempdir().unwrap();
+        let mut log = SessionLog::with_limit(dir.path(), "s1", 10).unwrap();
+        log.write(b"0123456").unwrap();
+        log.write(b"789").unwrap();
+        log.write(b"abc").unwrap();
+
+        let path = log_path(dir.path(), "s1");
+        assert_eq!(std::fs::read(&path).unwrap(), b"abc");
+        assert_eq!(std::fs::read(path.with_extension("log.1")).unwrap(), b"0123456789");
+    }
+}
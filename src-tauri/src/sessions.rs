@@ -1,8 +1,14 @@
 use anyhow::Result;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
 use uuid::Uuid;
 
+use crate::pty_manager::{CompletionSignal, SessionCounters, SessionStatus};
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct AgentSession {
     pub id: String,
@@ -10,8 +16,37 @@ pub struct AgentSession {
     pub name: Option<String>,
     pub status: String,
     pub scrollback: Option<String>,
+    /// `"utf8"` or `"base64"`. Output that isn't valid UTF-8 (e.g. an agent cat-ing a binary)
+    /// is stored base64-encoded so it round-trips exactly; the frontend should base64-decode
+    /// `scrollback` in that case, or simply use `get_scrollback`, which always returns raw bytes.
+    /// `"gzip"` means `archive_session_scrollback` moved it out and `scrollback` is the path of
+    /// the compressed file.
+    pub scrollback_encoding: String,
     pub created_at: i64,
     pub updated_at: i64,
+    /// The agent config the session was launched from; `None` for shells, ad-hoc commands
+    /// and configs deleted since.
+    pub agent_config_id: Option<String>,
+    /// Output and input byte totals, saved when the session stops; use `session_counters`
+    /// for live values.
+    pub bytes_out: i64,
+    pub bytes_in: i64,
+    /// Opaque color hint chosen by the frontend; not interpreted by the backend.
+    pub color: Option<String>,
+    /// Last terminal size set through `resize_pty` (80x24 until then), so the frontend can
+    /// restore the geometry before replaying scrollback.
+    pub cols: i64,
+    pub rows: i64,
+    /// Window title the running program last set (OSC 0/2). Live only, not stored; the
+    /// frontend should prefer `name` when it is set.
+    #[sqlx(default)]
+    pub terminal_title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScrollbackDelta {
+    pub data: Vec<u8>,
+    pub total_len: usize,
 }
 
 pub async fn create_session_db(pool: &SqlitePool, project_id: &str, name: &str) -> Result<AgentSession> {
@@ -27,6 +62,72 @@ pub async fn create_session_db(pool: &SqlitePool, project_id: &str, name: &str)
     Ok(session)
 }
 
+pub async fn set_session_color_db(pool: &SqlitePool, id: &str, color: Option<&str>) -> Result<()> {
+    let result = sqlx::query("UPDATE agent_sessions SET color = ? WHERE id = ?")
+        .bind(color)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Session not found"));
+    }
+    Ok(())
+}
+
+/// Remember the session's terminal size. Shells have no row, so nothing is stored for them.
+pub async fn save_session_size_db(pool: &SqlitePool, id: &str, cols: u16, rows: u16) -> Result<()> {
+    sqlx::query("UPDATE agent_sessions SET cols = ?, rows = ? WHERE id = ?")
+        .bind(cols as i64)
+        .bind(rows as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// An id that matches no config is stored as NULL rather than failing the spawn.
+pub async fn set_session_agent_config_db(pool: &SqlitePool, session_id: &str, config_id: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE agent_sessions SET agent_config_id = (SELECT id FROM agent_configs WHERE id = ?)
+         WHERE id = ?",
+    )
+        .bind(config_id)
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// `name`, or the first of `name (2)`, `name (3)`, … not used by another session in the
+/// project. `exclude_id` lets a session keep its own name when renamed.
+pub async fn unique_session_name_db(
+    pool: &SqlitePool,
+    project_id: &str,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<String> {
+    let taken: Vec<(String,)> = sqlx::query_as(
+        "SELECT name FROM agent_sessions
+         WHERE project_id = ? AND name IS NOT NULL AND id IS NOT COALESCE(?, '')",
+    )
+    .bind(project_id)
+    .bind(exclude_id)
+    .fetch_all(pool)
+    .await?;
+    let taken: std::collections::HashSet<String> = taken.into_iter().map(|(n,)| n).collect();
+    if !taken.contains(name) {
+        return Ok(name.to_string());
+    }
+    let mut n = 2;
+    loop {
+        let candidate = format!("{} ({})", name, n);
+        if !taken.contains(&candidate) {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
 pub async fn list_sessions_db(pool: &SqlitePool, project_id: &str) -> Result<Vec<AgentSession>> {
     let sessions = sqlx::query_as::<_, AgentSession>(
         "SELECT * FROM agent_sessions WHERE project_id = ? ORDER BY created_at DESC",
@@ -37,6 +138,39 @@ pub async fn list_sessions_db(pool: &SqlitePool, project_id: &str) -> Result<Vec
     Ok(sessions)
 }
 
+/// Replace each session's DB status with the live `PtyManager` status when the PTY is still
+/// in memory. Sessions absent from the manager keep their DB status.
+pub fn overlay_live_status<'a>(
+    sessions: impl IntoIterator<Item = &'a mut AgentSession>,
+    pty: &crate::pty_manager::PtyManager,
+) {
+    for session in sessions {
+        if let Some(status) = pty.get_status(&session.id) {
+            session.status = status.as_str().to_string();
+            session.terminal_title = pty.terminal_title(&session.id);
+        }
+    }
+}
+
+/// A session together with the name of the project it belongs to, for cross-project views.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SessionOverview {
+    #[sqlx(flatten)]
+    pub session: AgentSession,
+    pub project_name: String,
+}
+
+/// Every session across all projects, most recently updated first.
+pub async fn list_all_sessions_db(pool: &SqlitePool) -> Result<Vec<SessionOverview>> {
+    Ok(sqlx::query_as::<_, SessionOverview>(
+        "SELECT s.*, p.name AS project_name
+         FROM agent_sessions s JOIN projects p ON p.id = s.project_id
+         ORDER BY s.updated_at DESC, s.created_at DESC",
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
 pub async fn update_session_status_db(pool: &SqlitePool, id: &str, status: &str) -> Result<()> {
     sqlx::query(
         "UPDATE agent_sessions SET status = ?, updated_at = unixepoch() WHERE id = ?",
@@ -48,45 +182,371 @@ pub async fn update_session_status_db(pool: &SqlitePool, id: &str, status: &str)
     Ok(())
 }
 
-pub async fn save_scrollback_db(pool: &SqlitePool, id: &str, scrollback: &str) -> Result<()> {
+/// Persist raw scrollback without lossy conversion: valid UTF-8 is stored as text, anything
+/// else is base64-encoded and flagged via `scrollback_encoding`.
+pub async fn save_scrollback_db(pool: &SqlitePool, id: &str, scrollback: &[u8]) -> Result<()> {
+    let (text, encoding) = match std::str::from_utf8(scrollback) {
+        Ok(text) => (text.to_string(), "utf8"),
+        Err(_) => (base64::engine::general_purpose::STANDARD.encode(scrollback), "base64"),
+    };
     sqlx::query(
-        "UPDATE agent_sessions SET scrollback = ?, updated_at = unixepoch() WHERE id = ?",
+        "UPDATE agent_sessions SET scrollback = ?, scrollback_encoding = ?, updated_at = unixepoch()
+         WHERE id = ?",
     )
-    .bind(scrollback)
+    .bind(text)
+    .bind(encoding)
     .bind(id)
     .execute(pool)
     .await?;
     Ok(())
 }
 
+/// Persist a session whose process is gone: its scrollback (unless `None`, when scrollback
+/// persistence is off) and the `stopped` status.
+pub async fn store_stopped_session_db(
+    pool: &SqlitePool,
+    id: &str,
+    scrollback: Option<&[u8]>,
+    counters: SessionCounters,
+) -> Result<()> {
+    if let Some(scrollback) = scrollback {
+        save_scrollback_db(pool, id, scrollback).await?;
+    }
+    sqlx::query("UPDATE agent_sessions SET bytes_out = ?, bytes_in = ? WHERE id = ?")
+        .bind(counters.bytes_out as i64)
+        .bind(counters.bytes_in as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    update_session_status_db(pool, id, "stopped").await
+}
+
+/// Totals saved when the session stopped.
+pub async fn get_session_counters_db(pool: &SqlitePool, id: &str) -> Result<SessionCounters> {
+    let (bytes_out, bytes_in): (i64, i64) =
+        sqlx::query_as("SELECT bytes_out, bytes_in FROM agent_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+    Ok(SessionCounters { bytes_out: bytes_out as u64, bytes_in: bytes_in as u64 })
+}
+
+/// Mark sessions still recorded as running or idle as stopped, keeping their scrollback.
+/// Their PTYs died with the previous app run. Returns how many were orphaned.
+pub async fn mark_orphaned_sessions_db(pool: &SqlitePool) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE agent_sessions SET status = 'stopped', updated_at = unixepoch()
+         WHERE status != 'stopped'",
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Result of `archive_scrollback_db`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrollbackArchive {
+    pub path: String,
+    pub bytes: u64,
+    pub compressed_bytes: u64,
+}
+
+/// Move a stopped session's scrollback into `<dir>/<id>.gz`, leaving the path in the row.
+pub async fn archive_scrollback_db(
+    pool: &SqlitePool,
+    id: &str,
+    dir: &std::path::Path,
+) -> Result<ScrollbackArchive> {
+    use std::io::Write;
+
+    let (status, encoding): (String, String) =
+        sqlx::query_as("SELECT status, scrollback_encoding FROM agent_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+    if status != "stopped" {
+        return Err(anyhow::anyhow!("Only stopped sessions can be archived"));
+    }
+    if encoding == "gzip" {
+        return Err(anyhow::anyhow!("Scrollback is already archived"));
+    }
+    let scrollback = load_scrollback_db(pool, id).await?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.gz", id));
+    let tmp = dir.join(format!("{}.gz.tmp", id));
+    let mut encoder =
+        flate2::write::GzEncoder::new(std::fs::File::create(&tmp)?, flate2::Compression::default());
+    encoder.write_all(&scrollback)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&tmp, &path)?;
+
+    let path_str = path.to_string_lossy().to_string();
+    let updated = sqlx::query(
+        "UPDATE agent_sessions SET scrollback = ?, scrollback_encoding = 'gzip'
+         WHERE id = ? AND status = 'stopped'",
+    )
+    .bind(&path_str)
+    .bind(id)
+    .execute(pool)
+    .await;
+    match updated {
+        Ok(result) if result.rows_affected() == 1 => {}
+        // Raced with a delete or restart: keep the row as it was.
+        Ok(_) => {
+            let _ = std::fs::remove_file(&path);
+            return Err(anyhow::anyhow!("Only stopped sessions can be archived"));
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            return Err(e.into());
+        }
+    }
+    Ok(ScrollbackArchive {
+        compressed_bytes: std::fs::metadata(&path)?.len(),
+        path: path_str,
+        bytes: scrollback.len() as u64,
+    })
+}
+
+/// Delete a session's row and its archived scrollback, if any. Returns rows deleted.
+pub async fn delete_session_db(pool: &SqlitePool, id: &str) -> Result<u64> {
+    let archive: Option<(Option<String>,)> = sqlx::query_as(
+        "SELECT scrollback FROM agent_sessions WHERE id = ? AND scrollback_encoding = 'gzip'",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+    let result = sqlx::query("DELETE FROM agent_sessions WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if let Some((Some(path),)) = archive {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(result.rows_affected())
+}
+
+/// Persisted scrollback of a session no longer in the PTY manager; empty if none was saved.
+/// Archived scrollback is decompressed transparently.
+pub async fn load_scrollback_db(pool: &SqlitePool, id: &str) -> Result<Vec<u8>> {
+    let session = sqlx::query_as::<_, AgentSession>(
+        "SELECT * FROM agent_sessions WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(session) = session else { return Ok(Vec::new()) };
+    let text = session.scrollback.unwrap_or_default();
+    match session.scrollback_encoding.as_str() {
+        "base64" => Ok(base64::engine::general_purpose::STANDARD.decode(text)?),
+        "gzip" => {
+            use std::io::Read;
+            let file = std::fs::File::open(&text)
+                .map_err(|e| anyhow::anyhow!("Archived scrollback {} unreadable: {}", text, e))?;
+            let mut bytes = Vec::new();
+            flate2::read::GzDecoder::new(file).read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+        _ => Ok(text.into_bytes()),
+    }
+}
+
+/// How an agent's process is attached: a PTY for interactive agents, or plain pipes for
+/// batch jobs that don't need a terminal (resize is a no-op there).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpawnMode {
+    #[default]
+    Pty,
+    Piped,
+}
+
+/// Substrings of env var names whose values are never stored or returned.
+const SECRET_ENV_MARKERS: &[&str] = &[
+    "TOKEN", "SECRET", "PASSWORD", "PASSWD", "API_KEY", "APIKEY", "CREDENTIAL", "PRIVATE_KEY",
+];
+const REDACTED: &str = "[redacted]";
+
+/// The command line a session was actually launched with, for debugging agent behaviour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnInfo {
+    /// `command` resolved against `PATH`, or as given if it couldn't be found.
+    pub command: String,
+    /// After `$VAR` expansion, with every substituted value redacted.
+    pub args: Vec<String>,
+    pub cwd: String,
+    pub mode: SpawnMode,
+    /// The environment the agent got, with secret-looking values redacted.
+    pub env: BTreeMap<String, String>,
+}
+
+impl SpawnInfo {
+    /// `env` is the agent's full environment when it doesn't inherit the app's.
+    pub fn capture(
+        command: &str,
+        args: &[String],
+        cwd: &str,
+        mode: SpawnMode,
+        env: Option<&BTreeMap<String, String>>,
+    ) -> Self {
+        Self {
+            command: resolve_command(command, std::env::var_os("PATH").as_deref()),
+            args: args.to_vec(),
+            cwd: cwd.to_string(),
+            mode,
+            env: match env {
+                Some(env) => redact_env(env.clone()),
+                None => redact_env(std::env::vars()),
+            },
+        }
+    }
+}
+
+/// `args` expanded as `expand_env_vars` would, but with each substituted value replaced by
+/// `[redacted]`, so the result shows what ran without the values taken from the environment.
+fn redacted_expansion(
+    args: &[String],
+    lookup: impl Fn(&str) -> Option<String>,
+    strict: bool,
+) -> Result<Vec<String>> {
+    let redacted = |name: &str| lookup(name).map(|_| REDACTED.to_string());
+    args.iter()
+        .map(|arg| crate::agent_configs::expand_env_vars(arg, redacted, strict))
+        .collect()
+}
+
+fn resolve_command(command: &str, path: Option<&std::ffi::OsStr>) -> String {
+    if command.contains(std::path::MAIN_SEPARATOR) || command.contains('/') {
+        return command.to_string();
+    }
+    path.into_iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| dir.join(command))
+        .find(|candidate| candidate.is_file())
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|| command.to_string())
+}
+
+fn redact_env(vars: impl IntoIterator<Item = (String, String)>) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .map(|(key, value)| {
+            let upper = key.to_ascii_uppercase();
+            if SECRET_ENV_MARKERS.iter().any(|m| upper.contains(m)) {
+                (key, REDACTED.to_string())
+            } else {
+                (key, value)
+            }
+        })
+        .collect()
+}
+
+pub async fn save_spawn_info_db(pool: &SqlitePool, session_id: &str, info: &SpawnInfo) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO session_spawn_info (session_id, info) VALUES (?, ?)
+         ON CONFLICT(session_id) DO UPDATE SET info = excluded.info",
+    )
+    .bind(session_id)
+    .bind(serde_json::to_string(info)?)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_spawn_info_db(pool: &SqlitePool, session_id: &str) -> Result<Option<SpawnInfo>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT info FROM session_spawn_info WHERE session_id = ?")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.map(|(info,)| serde_json::from_str(&info)).transpose()?)
+}
+
+/// `completion_marker` / `sentinel_file` (usually from the agent config) let an agent that
+/// stays running signal it finished its task; each emits `session-task-complete`.
+/// `${VAR}` / `$VAR` in `args` are expanded first; with `strict_env` an unset one is an error.
+/// With `clear_env` the agent gets only `pty_manager::ISOLATED_ENV_VARS` instead of the app's
+/// whole environment. `output_filter` is a shell command the output is piped through before
+/// it reaches scrollback and listeners; if it fails, the raw output is shown instead.
+/// A session silent for `startup_timeout_secs` is flagged `failed_to_start`; with
+/// `kill_on_startup_timeout` it is also killed.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_agent(
     project_id: String,
     project_path: String,
     agent_name: String,
     command: String,
     args: Vec<String>,
+    mode: Option<SpawnMode>,
+    completion_marker: Option<String>,
+    sentinel_file: Option<String>,
+    agent_config_id: Option<String>,
+    strict_env: Option<bool>,
+    startup_timeout_secs: Option<u64>,
+    clear_env: Option<bool>,
+    output_filter: Option<String>,
+    kill_on_startup_timeout: Option<bool>,
     app: tauri::AppHandle,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<AgentSession, String> {
-    let session = create_session_db(&state.db, &project_id, &agent_name)
+    // `$VAR`s resolve against the app's environment, even when the agent won't inherit it:
+    // that is how a value is passed explicitly to an isolated agent.
+    if let Some(path) = sentinel_file.as_deref() {
+        crate::agent_configs::check_sentinel_path(path)?;
+    }
+    let strict = strict_env.unwrap_or(false);
+    let expanded = args
+        .iter()
+        .map(|arg| crate::agent_configs::expand_env_vars(arg, |name| std::env::var(name).ok(), strict))
+        .collect::<anyhow::Result<Vec<String>>>()
+        .map_err(|e| e.to_string())?;
+    let session = create_session_db(&state.db(), &project_id, &agent_name)
         .await
         .map_err(|e| e.to_string())?;
+    if let Some(config_id) = &agent_config_id {
+        set_session_agent_config_db(&state.db(), &session.id, config_id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
 
-    state
-        .pty
-        .spawn_agent(
-            session.id.clone(),
-            project_id,
-            &project_path,
-            &command,
-            &args,
-            state.terminal_tx.clone(),
-            app,
-        )
+    let mode = mode.unwrap_or_default();
+    let env = clear_env.unwrap_or(false).then(crate::pty_manager::isolated_env);
+    let recorded = redacted_expansion(&args, |name| std::env::var(name).ok(), strict)
         .map_err(|e| e.to_string())?;
+    let info = SpawnInfo::capture(&command, &recorded, &project_path, mode, env.as_ref());
+    eprintln!(
+        "Spawning session {}: {} {:?} in {} ({:?})",
+        session.id, info.command, info.args, info.cwd, info.mode
+    );
+    let args = expanded;
+
+    let completion = CompletionSignal { marker: completion_marker, sentinel_file };
+    let filter = output_filter.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    let tx = state.terminal_tx.clone();
+    match mode {
+        SpawnMode::Pty => state.pty.spawn_agent(
+            session.id.clone(), project_id, &project_path, &command, &args, env.as_ref(), filter,
+            completion, tx, app.clone(),
+        ),
+        SpawnMode::Piped => state.pty.spawn_agent_piped(
+            session.id.clone(), project_id, &project_path, &command, &args, env.as_ref(), filter,
+            completion, tx, app.clone(),
+        ),
+    }
+    .map_err(|e| e.to_string())?;
+    if let Some(secs) = startup_timeout_secs.filter(|s| *s > 0) {
+        let kill = kill_on_startup_timeout.unwrap_or(false);
+        watch_startup(app, session.id.clone(), Duration::from_secs(secs), kill);
+    }
 
-    update_session_status_db(&state.db, &session.id, "running")
+    save_spawn_info_db(&state.db(), &session.id, &info)
+        .await
+        .map_err(|e| e.to_string())?;
+    update_session_status_db(&state.db(), &session.id, "running")
         .await
         .map_err(|e| e.to_string())?;
 
@@ -95,36 +555,134 @@ pub async fn spawn_agent(
         "SELECT * FROM agent_sessions WHERE id = ?",
     )
     .bind(&session.id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db())
     .await
     .map_err(|e| e.to_string())?;
 
     Ok(updated)
 }
 
+/// After `timeout`, flag the session `failed_to_start` and emit `session-startup-timeout`
+/// (with its id) if it hasn't printed anything. The process keeps running unless `kill` is
+/// set; then it is killed and goes through the normal exit flow, ending up `stopped`.
+fn watch_startup(app: tauri::AppHandle, session_id: String, timeout: Duration, kill: bool) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let state = app.state::<crate::AppState>();
+        if !state.pty.mark_failed_to_start(&session_id, kill) {
+            return;
+        }
+        // A killed session's row is written by the exit flow; don't race it.
+        if !kill {
+            let db = state.db();
+            if let Err(e) = update_session_status_db(&db, &session_id, "failed_to_start").await {
+                eprintln!("Failed to update status for {}: {}", session_id, e);
+            }
+        }
+        let _ = app.emit("session-startup-timeout", session_id);
+    });
+}
+
+/// How the session was launched; `None` for shells and sessions from before this was recorded.
+#[tauri::command]
+pub async fn get_session_spawn_info(
+    session_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Option<SpawnInfo>, String> {
+    get_spawn_info_db(&state.db(), &session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sessions found still running when the current profile was opened, i.e. left behind by a
+/// crash or forced quit. Also pushed as `sessions-orphaned` after a profile switch.
+#[tauri::command]
+pub fn get_orphaned_session_count(state: tauri::State<'_, crate::AppState>) -> u64 {
+    state.orphaned_sessions.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 #[tauri::command]
 pub async fn list_sessions(
     project_id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Vec<AgentSession>, String> {
-    list_sessions_db(&state.db, &project_id)
+    let mut sessions = list_sessions_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    overlay_live_status(&mut sessions, &state.pty);
+    Ok(sessions)
+}
+
+/// What `rename_agent` does when another session in the project already has the name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameConflict {
+    #[default]
+    Allow,
+    Suffix,
+    Reject,
+}
+
+#[tauri::command]
+pub async fn suggest_session_name(
+    project_id: String,
+    name: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<String, String> {
+    unique_session_name_db(&state.db(), &project_id, &name, None)
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn list_all_sessions(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<SessionOverview>, String> {
+    let mut sessions = list_all_sessions_db(&state.db())
+        .await
+        .map_err(|e| e.to_string())?;
+    overlay_live_status(sessions.iter_mut().map(|o| &mut o.session), &state.pty);
+    Ok(sessions)
+}
+
+/// Returns the name actually applied, which differs from `name` when it was suffixed.
 #[tauri::command]
 pub async fn rename_agent(
     session_id: String,
     name: String,
+    on_conflict: Option<NameConflict>,
     state: tauri::State<'_, crate::AppState>,
-) -> Result<(), String> {
-    sqlx::query("UPDATE agent_sessions SET name = ?, updated_at = unixepoch() WHERE id = ?")
+) -> Result<String, String> {
+    let on_conflict = on_conflict.unwrap_or_default();
+    let name = if on_conflict == NameConflict::Allow {
+        name
+    } else {
+        let (project_id,): (String,) =
+            sqlx::query_as("SELECT project_id FROM agent_sessions WHERE id = ?")
+                .bind(&session_id)
+                .fetch_optional(&state.db())
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Session not found".to_string())?;
+        let unique = unique_session_name_db(&state.db(), &project_id, &name, Some(&session_id))
+            .await
+            .map_err(|e| e.to_string())?;
+        if unique != name && on_conflict == NameConflict::Reject {
+            return Err(format!("A session named '{}' already exists in this project", name));
+        }
+        unique
+    };
+
+    let result = sqlx::query("UPDATE agent_sessions SET name = ?, updated_at = unixepoch() WHERE id = ?")
         .bind(&name)
         .bind(&session_id)
-        .execute(&state.db)
+        .execute(&state.db())
         .await
         .map_err(|e| e.to_string())?;
-    Ok(())
+    if result.rows_affected() == 0 {
+        return Err("Session not found".to_string());
+    }
+    Ok(name)
 }
 
 #[tauri::command]
@@ -132,26 +690,80 @@ pub async fn kill_agent(
     session_id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    state.pty.kill_session(&session_id);
-    sqlx::query("DELETE FROM agent_sessions WHERE id = ?")
-        .bind(&session_id)
-        .execute(&state.db)
+    // Shell sessions only exist in the PtyManager, agents in both.
+    let was_live = state.pty.kill_session(&session_id);
+    let deleted = delete_session_db(&state.db(), &session_id)
         .await
         .map_err(|e| e.to_string())?;
+    if !was_live && deleted == 0 {
+        return Err("Session not found".to_string());
+    }
     Ok(())
 }
 
+/// Stop tracking a session but leave its process running: the row and the live entry are
+/// removed without killing the child. Advanced escape hatch: a detached process is never
+/// managed, saved or reaped again, and its terminal closes when the app quits, which ends
+/// programs that don't ignore SIGHUP.
+#[tauri::command]
+pub async fn detach_session(
+    session_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    let was_live = state.pty.detach_session(&session_id);
+    let deleted = delete_session_db(&state.db(), &session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !was_live && deleted == 0 {
+        return Err("Session not found".to_string());
+    }
+    Ok(())
+}
+
+/// `None` clears the color.
+#[tauri::command]
+pub async fn set_session_color(
+    session_id: String,
+    color: Option<String>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    set_session_color_db(&state.db(), &session_id, color.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Live counters for running sessions (shells included), saved totals for stopped ones.
+#[tauri::command]
+pub async fn session_counters(
+    session_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<SessionCounters, String> {
+    if let Some(counters) = state.pty.counters(&session_id) {
+        return Ok(counters);
+    }
+    get_session_counters_db(&state.db(), &session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns whether the PTY was actually resized. The new size is saved on the session row.
 #[tauri::command]
-pub fn resize_pty(
+pub async fn resize_pty(
     session_id: String,
     cols: u16,
     rows: u16,
     state: tauri::State<'_, crate::AppState>,
-) -> Result<(), String> {
-    state
+) -> Result<bool, String> {
+    let resized = state
         .pty
         .resize_session(&session_id, cols, rows)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if let (true, Some((cols, rows))) = (resized, crate::pty_manager::clamp_size(cols, rows)) {
+        save_session_size_db(&state.db(), &session_id, cols, rows)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(resized)
 }
 
 #[tauri::command]
@@ -166,10 +778,14 @@ pub fn write_to_agent(
         .map_err(|e| e.to_string())
 }
 
+/// Open a shell for the project. `cwd` (absolute or relative to `project_path`) must be a
+/// directory inside the project; defaults to the project root.
 #[tauri::command]
 pub async fn spawn_shell(
     session_id: String,
     project_path: String,
+    cwd: Option<String>,
+    kill_when_idle: Option<bool>,
     app: tauri::AppHandle,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
@@ -177,13 +793,69 @@ pub async fn spawn_shell(
     if state.pty.get_session(&session_id).is_some() {
         return Ok(());
     }
+    let cwd = match cwd {
+        Some(cwd) => crate::projects::resolve_subdir(std::path::Path::new(&project_path), &cwd)
+            .map_err(|e| e.to_string())?
+            .to_string_lossy()
+            .into_owned(),
+        None => project_path,
+    };
     state
         .pty
-        .spawn_shell(session_id, &project_path, state.terminal_tx.clone(), app)
+        .spawn_shell(
+            session_id,
+            &cwd,
+            kill_when_idle.unwrap_or(false),
+            state.terminal_tx.clone(),
+            app,
+        )
         .map(|_| ())
         .map_err(|e| e.to_string())
 }
 
+/// Open a shell in `subdir` of a project, e.g. a package in a monorepo.
+#[tauri::command]
+pub async fn spawn_shell_in_subdir(
+    session_id: String,
+    project_id: String,
+    subdir: String,
+    kill_when_idle: Option<bool>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    let project_path = crate::projects::get_project_path_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    spawn_shell(session_id, project_path, Some(subdir), kill_when_idle, app, state).await
+}
+
+/// Observer mode: while `readonly` is set, input to the session is rejected. Output keeps
+/// streaming. Not persisted; a restarted app starts every session writable.
+#[tauri::command]
+pub fn set_session_readonly(
+    session_id: String,
+    readonly: bool,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    state
+        .pty
+        .set_readonly(&session_id, readonly)
+        .map_err(|e| e.to_string())
+}
+
+/// Opt a session in or out of being killed once idle past the `idle_timeout_secs` setting.
+#[tauri::command]
+pub fn set_session_kill_when_idle(
+    session_id: String,
+    enabled: bool,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    state
+        .pty
+        .set_kill_when_idle(&session_id, enabled)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_scrollback(
     session_id: String,
@@ -195,18 +867,77 @@ pub async fn get_scrollback(
     }
 
     // Fallback: load from DB for stopped sessions
-    let session = sqlx::query_as::<_, AgentSession>(
-        "SELECT * FROM agent_sessions WHERE id = ?",
-    )
-    .bind(&session_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| e.to_string())?;
+    load_scrollback_db(&state.db(), &session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    Ok(session
-        .and_then(|s| s.scrollback)
-        .unwrap_or_default()
-        .into_bytes())
+/// Move a stopped session's scrollback out of the database into a gzip file in the data
+/// directory. `get_scrollback` and friends keep working on it.
+#[tauri::command]
+pub async fn archive_session_scrollback(
+    session_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<ScrollbackArchive, String> {
+    let status = state.pty.get_status(&session_id);
+    if status.as_ref().is_some_and(|s| !matches!(s, SessionStatus::Stopped)) {
+        return Err("Only stopped sessions can be archived".to_string());
+    }
+    let archive = archive_scrollback_db(&state.db(), &session_id, &state.data_dir.join("scrollback"))
+        .await
+        .map_err(|e| e.to_string())?;
+    // An exited session can still be in the PTY manager; drop it so its in-memory copy is
+    // freed and reads go to the archive. There is no process left to detach from.
+    if status.is_some() {
+        state.pty.detach_session(&session_id);
+    }
+    Ok(archive)
+}
+
+/// The last `lines` lines of a session's output with escape sequences stripped.
+#[tauri::command]
+pub async fn get_scrollback_tail(
+    session_id: String,
+    lines: usize,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<String, String> {
+    let scrollback = match state.pty.get_session(&session_id) {
+        Some((_, scrollback)) => scrollback,
+        None => load_scrollback_db(&state.db(), &session_id)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    Ok(crate::ansi::tail_lines(&scrollback, lines))
+}
+
+/// Scrollback after `offset` bytes, so the frontend can fetch deltas instead of the whole buffer.
+#[tauri::command]
+pub async fn get_scrollback_since(
+    session_id: String,
+    offset: usize,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<ScrollbackDelta, String> {
+    let (data, total_len) = match state.pty.get_scrollback_since(&session_id, offset) {
+        Some(delta) => delta,
+        None => {
+            let scrollback = load_scrollback_db(&state.db(), &session_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            crate::pty_manager::scrollback_delta(&scrollback, offset)
+        }
+    };
+    Ok(ScrollbackDelta { data, total_len })
+}
+
+#[tauri::command]
+pub fn get_session_log_path(
+    session_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Option<String> {
+    state
+        .pty
+        .log_path(&session_id)
+        .map(|p| p.to_string_lossy().into_owned())
 }
 
 #[cfg(test)]
@@ -231,6 +962,145 @@ mod tests {
 
         let sessions = list_sessions_db(&pool, "p1").await.unwrap();
         assert_eq!(sessions.len(), 1);
+
+        set_session_color_db(&pool, &session.id, Some("blue")).await.unwrap();
+        let sessions = list_sessions_db(&pool, "p1").await.unwrap();
+        assert_eq!(sessions[0].color.as_deref(), Some("blue"));
+        assert!(set_session_color_db(&pool, "missing", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_counters_saved_on_stop() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let session = create_session_db(&pool, "p1", "Agent").await.unwrap();
+        let initial = get_session_counters_db(&pool, &session.id).await.unwrap();
+        assert_eq!(initial, SessionCounters::default());
+
+        let counters = SessionCounters { bytes_out: 1234, bytes_in: 56 };
+        store_stopped_session_db(&pool, &session.id, Some(b"out"), counters).await.unwrap();
+        assert_eq!(get_session_counters_db(&pool, &session.id).await.unwrap(), counters);
+        assert!(get_session_counters_db(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_size_defaults_and_persists() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let session = create_session_db(&pool, "p1", "Agent").await.unwrap();
+        assert_eq!((session.cols, session.rows), (80, 24));
+
+        save_session_size_db(&pool, &session.id, 132, 40).await.unwrap();
+        let listed = list_sessions_db(&pool, "p1").await.unwrap();
+        assert_eq!((listed[0].cols, listed[0].rows), (132, 40));
+    }
+
+    #[tokio::test]
+    async fn test_stop_without_persisting_scrollback() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let session = create_session_db(&pool, "p1", "Agent").await.unwrap();
+
+        store_stopped_session_db(&pool, &session.id, None, SessionCounters::default()).await.unwrap();
+        let listed = list_sessions_db(&pool, "p1").await.unwrap();
+        assert_eq!(listed[0].status, "stopped");
+        assert_eq!(listed[0].scrollback, None);
+        assert!(load_scrollback_db(&pool, &session.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_remembers_agent_config() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let session = create_session_db(&pool, "p1", "Claude").await.unwrap();
+        assert_eq!(session.agent_config_id, None);
+
+        set_session_agent_config_db(&pool, &session.id, "builtin-claude").await.unwrap();
+        let listed = list_sessions_db(&pool, "p1").await.unwrap();
+        assert_eq!(listed[0].agent_config_id.as_deref(), Some("builtin-claude"));
+
+        // Deleting the config keeps the session, without the link.
+        crate::agent_configs::add_db(&pool, "Other", "other", "[]").await.unwrap();
+        crate::agent_configs::delete_db(&pool, "builtin-claude").await.unwrap();
+        assert_eq!(list_sessions_db(&pool, "p1").await.unwrap()[0].agent_config_id, None);
+
+        set_session_agent_config_db(&pool, &session.id, "missing").await.unwrap();
+        assert_eq!(list_sessions_db(&pool, "p1").await.unwrap()[0].agent_config_id, None);
+    }
+
+    #[test]
+    fn test_redacted_expansion_hides_substituted_values() {
+        let lookup = |name: &str| (name == "API_KEY").then(|| "sk-123".to_string());
+        let args = ["--key=$API_KEY".to_string(), "${MISSING}".to_string(), "plain".to_string()];
+        assert_eq!(
+            redacted_expansion(&args, lookup, false).unwrap(),
+            ["--key=[redacted]", "${MISSING}", "plain"]
+        );
+        assert!(redacted_expansion(&args, lookup, true).is_err());
+    }
+
+    #[test]
+    fn test_redact_env() {
+        let env = redact_env([
+            ("HOME".to_string(), "/home/me".to_string()),
+            ("ANTHROPIC_API_KEY".to_string(), "sk-123".to_string()),
+            ("github_token".to_string(), "ghp".to_string()),
+        ]);
+        assert_eq!(env["HOME"], "/home/me");
+        assert_eq!(env["ANTHROPIC_API_KEY"], REDACTED);
+        assert_eq!(env["github_token"], REDACTED);
+    }
+
+    #[test]
+    fn test_resolve_command_searches_path() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("agent"), "").unwrap();
+        let path = std::env::join_paths([std::path::Path::new("/nonexistent"), dir.path()]).unwrap();
+
+        let resolved = resolve_command("agent", Some(&path));
+        assert_eq!(resolved, dir.path().join("agent").to_string_lossy());
+        assert_eq!(resolve_command("missing", Some(&path)), "missing");
+        assert_eq!(resolve_command("./agent", Some(&path)), "./agent");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_info_round_trip_and_cascade() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Test', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let session = create_session_db(&pool, "p1", "Agent").await.unwrap();
+        assert!(get_spawn_info_db(&pool, &session.id).await.unwrap().is_none());
+
+        let args = ["-c".to_string(), "true".to_string()];
+        let info = SpawnInfo::capture("sh", &args, "/tmp", SpawnMode::Piped, None);
+        save_spawn_info_db(&pool, &session.id, &info).await.unwrap();
+        assert_eq!(get_spawn_info_db(&pool, &session.id).await.unwrap(), Some(info));
+
+        sqlx::query("DELETE FROM agent_sessions").execute(&pool).await.unwrap();
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM session_spawn_info")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count.0, 0);
     }
 
     #[tokio::test]
@@ -249,4 +1119,119 @@ mod tests {
         let sessions = list_sessions_db(&pool, "p1").await.unwrap();
         assert_eq!(sessions[0].status, "running");
     }
+
+    #[tokio::test]
+    async fn test_unique_session_name() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'A', '/a'), ('p2', 'B', '/b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unique_session_name_db(&pool, "p1", "agent", None).await.unwrap(), "agent");
+
+        let first = create_session_db(&pool, "p1", "agent").await.unwrap();
+        create_session_db(&pool, "p1", "agent (2)").await.unwrap();
+        create_session_db(&pool, "p2", "agent (3)").await.unwrap();
+        assert_eq!(unique_session_name_db(&pool, "p1", "agent", None).await.unwrap(), "agent (3)");
+        assert_eq!(
+            unique_session_name_db(&pool, "p1", "agent", Some(&first.id)).await.unwrap(),
+            "agent"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scrollback_roundtrips_text_and_binary() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'T', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let s = create_session_db(&pool, "p1", "S1").await.unwrap();
+
+        save_scrollback_db(&pool, &s.id, "héllo\r\n".as_bytes()).await.unwrap();
+        assert_eq!(load_scrollback_db(&pool, &s.id).await.unwrap(), "héllo\r\n".as_bytes());
+        assert_eq!(list_sessions_db(&pool, "p1").await.unwrap()[0].scrollback_encoding, "utf8");
+
+        let binary = vec![0x00, 0xff, 0xfe, b'a', 0x80];
+        save_scrollback_db(&pool, &s.id, &binary).await.unwrap();
+        assert_eq!(load_scrollback_db(&pool, &s.id).await.unwrap(), binary);
+        assert_eq!(list_sessions_db(&pool, "p1").await.unwrap()[0].scrollback_encoding, "base64");
+    }
+
+    #[tokio::test]
+    async fn test_archive_scrollback_roundtrips_and_cleans_up() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'T', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let s = create_session_db(&pool, "p1", "S1").await.unwrap();
+        let archive_dir = dir.path().join("scrollback");
+        let binary = vec![0x00, 0xff, b'a', b'a', b'a', b'a'];
+        save_scrollback_db(&pool, &s.id, &binary).await.unwrap();
+
+        update_session_status_db(&pool, &s.id, "running").await.unwrap();
+        let err = archive_scrollback_db(&pool, &s.id, &archive_dir).await.unwrap_err();
+        assert_eq!(err.to_string(), "Only stopped sessions can be archived");
+
+        update_session_status_db(&pool, &s.id, "stopped").await.unwrap();
+        let archive = archive_scrollback_db(&pool, &s.id, &archive_dir).await.unwrap();
+        assert_eq!(archive.bytes, binary.len() as u64);
+        assert!(std::path::Path::new(&archive.path).exists());
+        let listed = list_sessions_db(&pool, "p1").await.unwrap();
+        assert_eq!(listed[0].scrollback_encoding, "gzip");
+        assert_eq!(listed[0].scrollback.as_deref(), Some(archive.path.as_str()));
+        assert_eq!(load_scrollback_db(&pool, &s.id).await.unwrap(), binary);
+        assert!(archive_scrollback_db(&pool, &s.id, &archive_dir).await.is_err());
+
+        assert_eq!(delete_session_db(&pool, &s.id).await.unwrap(), 1);
+        assert!(!std::path::Path::new(&archive.path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_keeps_db_status_for_absent_sessions() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'T', '/tmp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let s = create_session_db(&pool, "p1", "S1").await.unwrap();
+        update_session_status_db(&pool, &s.id, "running").await.unwrap();
+
+        let mut sessions = list_sessions_db(&pool, "p1").await.unwrap();
+        overlay_live_status(&mut sessions, &crate::pty_manager::PtyManager::new());
+        assert_eq!(sessions[0].status, "running");
+    }
+
+    #[tokio::test]
+    async fn test_list_all_sessions_across_projects() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1', 'Alpha', '/a'), ('p2', 'Beta', '/b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let a = create_session_db(&pool, "p1", "A").await.unwrap();
+        create_session_db(&pool, "p2", "B").await.unwrap();
+        sqlx::query("UPDATE agent_sessions SET updated_at = updated_at + 10 WHERE id = ?")
+            .bind(&a.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let all = list_all_sessions_db(&pool).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].session.id, a.id);
+        assert_eq!(all[0].project_name, "Alpha");
+        assert_eq!(all[1].project_name, "Beta");
+    }
 }
@@ -0,0 +1,258 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+
+/// "true" enables the awaiting-input heuristic in the PTY reader loop. Off by default.
+pub const PROMPT_DETECTION: &str = "prompt_detection";
+/// JSON array of prompt patterns; falls back to `DEFAULT_PROMPT_PATTERNS` when unset.
+pub const PROMPT_PATTERNS: &str = "prompt_patterns";
+/// Seconds without output or input before a session counts as idle; unset or 0 disables.
+pub const IDLE_TIMEOUT_SECS: &str = "idle_timeout_secs";
+/// Seconds between `session-heartbeat` events; unset or 0 disables.
+pub const HEARTBEAT_SECS: &str = "heartbeat_secs";
+/// Bytes per PTY read for newly spawned sessions; unset uses `DEFAULT_READ_BUFFER_SIZE`.
+pub const READ_BUFFER_SIZE: &str = "read_buffer_size";
+/// "true" tees each new session's raw output to `logs/<session_id>.log` in the app data dir.
+pub const SESSION_LOGS: &str = "session_logs";
+/// "false" keeps session output in memory only: nothing is written to the database when a
+/// session stops, so its history is gone once it is closed. Defaults to true.
+pub const PERSIST_SCROLLBACK: &str = "persist_scrollback";
+/// Largest single write to a session in bytes; unset uses `DEFAULT_MAX_WRITE_BYTES`.
+pub const MAX_WRITE_BYTES: &str = "max_write_bytes";
+/// "true" closes the linked GitHub issue when a github-sourced task is moved to "done".
+pub const CLOSE_ISSUES_ON_DONE: &str = "close_issues_on_done";
+/// JSON array of directories searched for existing clones by `add_project_from_github_url`.
+pub const PROJECT_SEARCH_DIRS: &str = "project_search_dirs";
+/// Commit message template seeded by `get_commit_template`; `commit_template:<project_id>`
+/// overrides it for one project. See `expand_commit_template` for placeholders.
+pub const COMMIT_TEMPLATE: &str = "commit_template";
+/// JSON `SpawnMdSchema` used by `validate_spawn_md` when the caller passes none.
+pub const SPAWN_MD_SCHEMA: &str = "spawn_md_schema";
+/// JSON `NotificationRules` deciding which session exits emit `session-notification`.
+pub const NOTIFICATION_RULES: &str = "notification_rules";
+
+pub async fn get_setting_db(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|(v,)| v))
+}
+
+pub async fn set_setting_db(pool: &SqlitePool, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = unixepoch()",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_bool_setting(pool: &SqlitePool, key: &str, default: bool) -> Result<bool> {
+    Ok(get_setting_db(pool, key)
+        .await?
+        .map(|v| v == "true")
+        .unwrap_or(default))
+}
+
+/// Check `value` parses the way `key` is read, so a bad value is refused by `set_setting`
+/// instead of being stored. Keys without a known format accept anything.
+pub fn validate_setting(key: &str, value: &str) -> Result<()> {
+    let checked: Result<()> = match key {
+        PROMPT_DETECTION | SESSION_LOGS | PERSIST_SCROLLBACK | CLOSE_ISSUES_ON_DONE => {
+            if value == "true" || value == "false" {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("expected \"true\" or \"false\""))
+            }
+        }
+        PROMPT_PATTERNS => serde_json::from_str::<Vec<String>>(value).map(drop).map_err(Into::into),
+        PROJECT_SEARCH_DIRS => serde_json::from_str::<Vec<std::path::PathBuf>>(value)
+            .map(drop)
+            .map_err(Into::into),
+        IDLE_TIMEOUT_SECS | HEARTBEAT_SECS => value.trim().parse::<u64>().map(drop).map_err(Into::into),
+        READ_BUFFER_SIZE | MAX_WRITE_BYTES => value.trim().parse::<usize>().map(drop).map_err(Into::into),
+        NOTIFICATION_RULES => serde_json::from_str::<crate::notifications::NotificationRules>(value)
+            .map(drop)
+            .map_err(Into::into),
+        SPAWN_MD_SCHEMA => serde_json::from_str::<crate::spawn_md::SpawnMdSchema>(value)
+            .map(drop)
+            .map_err(Into::into),
+        _ => Ok(()),
+    };
+    checked.map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))
+}
+
+/// The stored value of `key` parsed with `parse`, or `default` when it is unset. A value that
+/// doesn't parse (e.g. saved before validation existed) is logged and also gives `default`,
+/// so one bad setting can't keep the others from being applied.
+async fn parsed_setting<T>(
+    pool: &SqlitePool,
+    key: &str,
+    default: T,
+    parse: impl FnOnce(&str) -> Result<T>,
+) -> Result<T> {
+    let Some(value) = get_setting_db(pool, key).await? else { return Ok(default) };
+    match parse(&value) {
+        Ok(parsed) => Ok(parsed),
+        Err(e) => {
+            eprintln!("Ignoring invalid {} setting {:?}: {}", key, value, e);
+            Ok(default)
+        }
+    }
+}
+
+/// Push settings that the PTY reader threads consult into the in-memory `PtyManager`.
+/// Runs at startup and after every `set_setting`.
+pub async fn apply_runtime_settings(pool: &SqlitePool, pty: &crate::pty_manager::PtyManager) -> Result<()> {
+    let patterns: Vec<String> = if get_bool_setting(pool, PROMPT_DETECTION, false).await? {
+        let defaults = crate::prompt_detect::DEFAULT_PROMPT_PATTERNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        parsed_setting(pool, PROMPT_PATTERNS, defaults, |v| Ok(serde_json::from_str(v)?)).await?
+    } else {
+        Vec::new()
+    };
+    *pty.prompt_patterns.lock().unwrap() = patterns;
+
+    let idle_secs: u64 = parsed_setting(pool, IDLE_TIMEOUT_SECS, 0, |v| Ok(v.trim().parse()?)).await?;
+    *pty.idle_timeout.lock().unwrap() =
+        (idle_secs > 0).then(|| std::time::Duration::from_secs(idle_secs));
+
+    let heartbeat_secs: u64 = parsed_setting(pool, HEARTBEAT_SECS, 0, |v| Ok(v.trim().parse()?)).await?;
+    *pty.heartbeat_interval.lock().unwrap() =
+        (heartbeat_secs > 0).then(|| std::time::Duration::from_secs(heartbeat_secs));
+
+    let buf_size: usize = parsed_setting(
+        pool,
+        READ_BUFFER_SIZE,
+        crate::pty_manager::DEFAULT_READ_BUFFER_SIZE,
+        |v| Ok(v.trim().parse::<usize>()?.clamp(1024, 1024 * 1024)),
+    )
+    .await?;
+    *pty.read_buffer_size.lock().unwrap() = buf_size;
+
+    *pty.session_logs.lock().unwrap() = get_bool_setting(pool, SESSION_LOGS, false).await?;
+    *pty.persist_scrollback.lock().unwrap() = get_bool_setting(pool, PERSIST_SCROLLBACK, true).await?;
+
+    let max_write: usize = parsed_setting(
+        pool,
+        MAX_WRITE_BYTES,
+        crate::pty_manager::DEFAULT_MAX_WRITE_BYTES,
+        |v| Ok(v.trim().parse::<usize>()?.max(1024)),
+    )
+    .await?;
+    *pty.max_write_bytes.lock().unwrap() = max_write;
+
+    *pty.notification_rules.lock().unwrap() =
+        parsed_setting(pool, NOTIFICATION_RULES, Default::default(), |v| Ok(serde_json::from_str(v)?))
+            .await?;
+    Ok(())
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub async fn get_setting(
+    key: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Option<String>, String> {
+    get_setting_db(&state.db(), &key).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_setting(
+    key: String,
+    value: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<(), String> {
+    validate_setting(&key, &value).map_err(|e| e.to_string())?;
+    set_setting_db(&state.db(), &key, &value)
+        .await
+        .map_err(|e| e.to_string())?;
+    apply_runtime_settings(&state.db(), &state.pty)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::pty_manager::PtyManager;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_set_and_get_setting() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        assert_eq!(get_setting_db(&pool, "k").await.unwrap(), None);
+        set_setting_db(&pool, "k", "v1").await.unwrap();
+        set_setting_db(&pool, "k", "v2").await.unwrap();
+        assert_eq!(get_setting_db(&pool, "k").await.unwrap().as_deref(), Some("v2"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_detection_is_opt_in() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let pty = PtyManager::new();
+
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+        assert!(pty.prompt_patterns.lock().unwrap().is_empty());
+
+        set_setting_db(&pool, PROMPT_DETECTION, "true").await.unwrap();
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+        assert!(!pty.prompt_patterns.lock().unwrap().is_empty());
+
+        set_setting_db(&pool, PROMPT_PATTERNS, r#"["continue?"]"#).await.unwrap();
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+        assert_eq!(*pty.prompt_patterns.lock().unwrap(), vec!["continue?".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_setting() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let pty = PtyManager::new();
+
+        set_setting_db(&pool, IDLE_TIMEOUT_SECS, "600").await.unwrap();
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+        assert_eq!(*pty.idle_timeout.lock().unwrap(), Some(std::time::Duration::from_secs(600)));
+
+        set_setting_db(&pool, IDLE_TIMEOUT_SECS, "0").await.unwrap();
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+        assert_eq!(*pty.idle_timeout.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_setting_rejects_unparseable_values() {
+        assert!(validate_setting(IDLE_TIMEOUT_SECS, "600").is_ok());
+        assert!(validate_setting(IDLE_TIMEOUT_SECS, "ten").is_err());
+        assert!(validate_setting(PROMPT_PATTERNS, "not json").is_err());
+        assert!(validate_setting(PERSIST_SCROLLBACK, "yes").is_err());
+        assert!(validate_setting(NOTIFICATION_RULES, "{}").is_ok());
+        assert!(validate_setting("unknown_key", "anything").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_stored_setting_falls_back_without_blocking_others() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        let pty = PtyManager::new();
+
+        set_setting_db(&pool, PROMPT_DETECTION, "true").await.unwrap();
+        set_setting_db(&pool, PROMPT_PATTERNS, "not json").await.unwrap();
+        set_setting_db(&pool, IDLE_TIMEOUT_SECS, "ten").await.unwrap();
+        set_setting_db(&pool, HEARTBEAT_SECS, "30").await.unwrap();
+        apply_runtime_settings(&pool, &pty).await.unwrap();
+
+        assert_eq!(pty.prompt_patterns.lock().unwrap().len(), crate::prompt_detect::DEFAULT_PROMPT_PATTERNS.len());
+        assert_eq!(*pty.idle_timeout.lock().unwrap(), None);
+        assert_eq!(*pty.heartbeat_interval.lock().unwrap(), Some(std::time::Duration::from_secs(30)));
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+/// What a team requires of `.spawn.md`. Stored as JSON under the `spawn_md_schema` setting;
+/// missing fields default to empty, so only structural problems are reported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawnMdSchema {
+    /// Keys that must appear in the `---` front matter.
+    pub required_keys: Vec<String>,
+    /// Heading titles (any level, case-insensitive) that must appear with some content.
+    pub required_sections: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpawnMdWarning {
+    /// 1-based; `None` for something missing from the file as a whole.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl SpawnMdWarning {
+    fn at(line: usize, message: String) -> Self {
+        Self { line: Some(line), message }
+    }
+
+    fn missing(message: String) -> Self {
+        Self { line: None, message }
+    }
+}
+
+struct Heading<'a> {
+    line: usize,
+    level: usize,
+    title: &'a str,
+    has_content: bool,
+}
+
+/// Check `content` against `schema` without changing anything. Warnings come in file order,
+/// followed by whatever is missing.
+pub fn validate(content: &str, schema: &SpawnMdSchema) -> Vec<SpawnMdWarning> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut warnings = Vec::new();
+
+    // Front matter: `---` on the first line up to the next `---`.
+    let mut keys: Vec<&str> = Vec::new();
+    let mut body_start = 0;
+    if lines.first().is_some_and(|l| l.trim_end() == "---") {
+        match lines.iter().skip(1).position(|l| l.trim_end() == "---") {
+            Some(end) => {
+                for (i, line) in lines[1..=end].iter().enumerate() {
+                    if line.starts_with([' ', '\t', '#', '-']) || line.trim().is_empty() {
+                        continue; // nested value, comment or list item
+                    }
+                    let Some((key, _)) = line.split_once(':') else {
+                        let message = format!("Front matter line is not `key: value`: {}", line);
+                        warnings.push(SpawnMdWarning::at(i + 2, message));
+                        continue;
+                    };
+                    let key = key.trim();
+                    if keys.contains(&key) {
+                        let message = format!("Duplicate front matter key '{}'", key);
+                        warnings.push(SpawnMdWarning::at(i + 2, message));
+                    }
+                    keys.push(key);
+                }
+                body_start = end + 2;
+            }
+            None => {
+                let message = "Front matter is never closed with `---`".to_string();
+                warnings.push(SpawnMdWarning::at(1, message));
+            }
+        }
+    }
+
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate().skip(body_start) {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let is_heading = !in_fence
+            && (1..=6).contains(&level)
+            && trimmed[level..].starts_with(' ');
+        if is_heading {
+            let title = trimmed[level..].trim();
+            headings.push(Heading { line: i + 1, level, title, has_content: false });
+        } else if !line.trim().is_empty() {
+            // Content counts for the innermost open heading and every enclosing one.
+            let mut level = usize::MAX;
+            for heading in headings.iter_mut().rev() {
+                if heading.level < level {
+                    heading.has_content = true;
+                    level = heading.level;
+                }
+            }
+        }
+    }
+
+    for required in &schema.required_sections {
+        let matching: Vec<&Heading> =
+            headings.iter().filter(|h| h.title.eq_ignore_ascii_case(required)).collect();
+        match matching.as_slice() {
+            [] => warnings.push(SpawnMdWarning::missing(format!("Missing section '{}'", required))),
+            [first, rest @ ..] => {
+                if !first.has_content {
+                    let message = format!("Section '{}' is empty", required);
+                    warnings.push(SpawnMdWarning::at(first.line, message));
+                }
+                for dup in rest {
+                    let message = format!("Section '{}' appears more than once", required);
+                    warnings.push(SpawnMdWarning::at(dup.line, message));
+                }
+            }
+        }
+    }
+    warnings.sort_by_key(|w| w.line.unwrap_or(usize::MAX));
+
+    for key in &schema.required_keys {
+        if !keys.contains(&key.as_str()) {
+            warnings.push(SpawnMdWarning::missing(format!("Missing front matter key '{}'", key)));
+        }
+    }
+    warnings
+}
+
+// --- Tauri commands ---
+
+/// Report problems with the project's `.spawn.md`. `schema` defaults to the
+/// `spawn_md_schema` setting. The file is only read.
+#[tauri::command]
+pub async fn validate_spawn_md(
+    path: String,
+    schema: Option<SpawnMdSchema>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<SpawnMdWarning>, String> {
+    use crate::settings::{get_setting_db, SPAWN_MD_SCHEMA};
+
+    let schema = match schema {
+        Some(schema) => schema,
+        None => match get_setting_db(&state.db(), SPAWN_MD_SCHEMA).await.map_err(|e| e.to_string())? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Invalid {} setting: {}", SPAWN_MD_SCHEMA, e))?,
+            None => SpawnMdSchema::default(),
+        },
+    };
+    let content = crate::projects::read_markdown_file(path, ".spawn.md".to_string(), Some(true))?
+        .ok_or_else(|| ".spawn.md not found".to_string())?;
+    Ok(validate(&content, &schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> SpawnMdSchema {
+        SpawnMdSchema {
+            required_keys: vec!["owner".into(), "stack".into()],
+            required_sections: vec!["Overview".into(), "Testing".into()],
+        }
+    }
+
+    #[test]
+    fn test_valid_file_has_no_warnings() {
+        let md = "---\nowner: team\nstack:\n  - rust\n---\n# Overview\nText\n## Testing\n`cargo test`\n";
+        assert_eq!(validate(md, &schema()), []);
+        assert_eq!(validate("anything", &SpawnMdSchema::default()), []);
+    }
+
+    #[test]
+    fn test_reports_lines_and_missing_items() {
+        let md = "---\nowner: a\nowner: b\nnot a pair\n---\n# overview\n## Notes\nx\n```\n# Testing\n```\n";
+        let warnings = validate(md, &schema());
+        let summary: Vec<(Option<usize>, &str)> =
+            warnings.iter().map(|w| (w.line, w.message.as_str())).collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(3), "Duplicate front matter key 'owner'"),
+                (Some(4), "Front matter line is not `key: value`: not a pair"),
+                (None, "Missing section 'Testing'"),
+                (None, "Missing front matter key 'stack'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_duplicate_and_unclosed() {
+        let md = "---\nowner: a\n# Overview\n\n# Testing\nok\n# Testing\n";
+        let warnings = validate(md, &schema());
+        assert_eq!(warnings[0], SpawnMdWarning::at(1, "Front matter is never closed with `---`".into()));
+        assert!(warnings.contains(&SpawnMdWarning::at(3, "Section 'Overview' is empty".into())));
+        assert!(warnings.contains(&SpawnMdWarning::at(7, "Section 'Testing' appears more than once".into())));
+    }
+}
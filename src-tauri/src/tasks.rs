@@ -1,6 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use tauri::Emitter;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -17,20 +18,136 @@ pub struct Task {
     pub updated_at: i64,
 }
 
+/// Result of `update_task_status`. The status change itself succeeded; closing the linked
+/// GitHub issue (opt-in via the `close_issues_on_done` setting) is reported separately.
+#[derive(Debug, Default, Serialize)]
+pub struct StatusUpdate {
+    pub closed_issue: Option<i64>,
+    pub close_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TaskActivity {
+    pub id: String,
+    pub task_id: String,
+    pub session_id: Option<String>,
+    /// e.g. `session_exited`
+    pub kind: String,
+    pub content: String,
+    pub created_at: i64,
+}
+
+/// Lines of session output kept in a task's exit transcript.
+const TRANSCRIPT_LINES: usize = 40;
+
+/// Board columns for projects that haven't configured their own.
+pub const DEFAULT_TASK_STATUSES: &[&str] = &["todo", "in_progress", "done"];
+
+/// The project's ordered task statuses, or `DEFAULT_TASK_STATUSES` if none are configured.
+pub async fn get_task_statuses_db(pool: &SqlitePool, project_id: &str) -> Result<Vec<String>> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "SELECT status FROM project_task_statuses WHERE project_id = ? ORDER BY position",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    if rows.is_empty() {
+        return Ok(DEFAULT_TASK_STATUSES.iter().map(|s| s.to_string()).collect());
+    }
+    Ok(rows.into_iter().map(|(s,)| s).collect())
+}
+
+/// Replace the project's status list. Existing tasks keep their status even if it's no longer listed.
+pub async fn set_task_statuses_db(pool: &SqlitePool, project_id: &str, statuses: &[String]) -> Result<()> {
+    if statuses.is_empty() {
+        return Err(anyhow::anyhow!("At least one status is required"));
+    }
+    let mut seen = std::collections::HashSet::new();
+    for status in statuses {
+        if status.trim().is_empty() {
+            return Err(anyhow::anyhow!("Status names cannot be empty"));
+        }
+        if !seen.insert(status.as_str()) {
+            return Err(anyhow::anyhow!("Duplicate status '{}'", status));
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM project_task_statuses WHERE project_id = ?")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+    for (position, status) in statuses.iter().enumerate() {
+        sqlx::query("INSERT INTO project_task_statuses (project_id, status, position) VALUES (?, ?, ?)")
+            .bind(project_id)
+            .bind(status)
+            .bind(position as i64)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Board columns to render: the project's configured statuses in order, then any other
+/// status its tasks still carry (e.g. from a column that was since removed), alphabetically.
+pub async fn list_task_statuses_db(pool: &SqlitePool, project_id: &str) -> Result<Vec<String>> {
+    let mut statuses = get_task_statuses_db(pool, project_id).await?;
+    let in_use: Vec<(String,)> =
+        sqlx::query_as("SELECT DISTINCT status FROM tasks WHERE project_id = ? ORDER BY status")
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?;
+    for (status,) in in_use {
+        if !statuses.contains(&status) {
+            statuses.push(status);
+        }
+    }
+    Ok(statuses)
+}
+
+/// Number of tasks per board column, including empty columns (see `list_task_statuses_db`).
+pub async fn task_counts_db(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<std::collections::BTreeMap<String, usize>> {
+    let mut counts: std::collections::BTreeMap<String, usize> = list_task_statuses_db(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|status| (status, 0))
+        .collect();
+    for task in list_tasks_db(pool, project_id).await? {
+        *counts.entry(task.status).or_default() += 1;
+    }
+    Ok(counts)
+}
+
+/// `status` must be one of the project's statuses; without one the task goes in the first column.
 pub async fn create_task_db(
     pool: &SqlitePool,
     project_id: &str,
     title: &str,
     description: Option<&str>,
+    status: Option<&str>,
 ) -> Result<Task> {
+    let statuses = get_task_statuses_db(pool, project_id).await?;
+    let status = match status {
+        Some(status) if !statuses.iter().any(|s| s == status) => {
+            return Err(anyhow::anyhow!("Unknown status '{}' for this project", status));
+        }
+        Some(status) => status,
+        None => statuses[0].as_str(),
+    };
     let id = Uuid::new_v4().to_string();
     let task = sqlx::query_as::<_, Task>(
-        "INSERT INTO tasks (id, project_id, title, description) VALUES (?, ?, ?, ?) RETURNING *",
+        "INSERT INTO tasks (id, project_id, title, description, status)
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
     )
     .bind(&id)
     .bind(project_id)
     .bind(title)
     .bind(description)
+    .bind(status)
     .fetch_one(pool)
     .await?;
     Ok(task)
@@ -46,17 +163,273 @@ pub async fn list_tasks_db(pool: &SqlitePool, project_id: &str) -> Result<Vec<Ta
     Ok(tasks)
 }
 
+/// Issue numbers already imported as tasks in the project.
+pub async fn imported_issue_numbers_db(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<std::collections::HashSet<i64>> {
+    let rows: Vec<(i64,)> = sqlx::query_as(
+        "SELECT github_issue_number FROM tasks
+         WHERE project_id = ? AND github_issue_number IS NOT NULL",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|(n,)| n).collect())
+}
+
+pub async fn get_task_db(pool: &SqlitePool, id: &str) -> Result<Task> {
+    sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Task not found"))
+}
+
+/// The GitHub issue to close when `task` moves to `status`: only for github-sourced tasks
+/// entering "done".
+pub fn issue_to_close(task: &Task, status: &str) -> Option<i64> {
+    if task.source == "github" && status == "done" && task.status != "done" {
+        task.github_issue_number
+    } else {
+        None
+    }
+}
+
 pub async fn update_task_status_db(pool: &SqlitePool, id: &str, status: &str) -> Result<()> {
-    sqlx::query(
+    let (project_id,): (String,) = sqlx::query_as("SELECT project_id FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+    if !get_task_statuses_db(pool, &project_id).await?.iter().any(|s| s == status) {
+        return Err(anyhow::anyhow!("Unknown status '{}' for this project", status));
+    }
+
+    let result = sqlx::query(
         "UPDATE tasks SET status = ?, updated_at = unixepoch() WHERE id = ?",
     )
     .bind(status)
     .bind(id)
     .execute(pool)
     .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Task not found"));
+    }
     Ok(())
 }
 
+/// Move a task to another project. The linked session belongs to the old project, so it is cleared.
+/// A status the destination board has no column for becomes its first column.
+pub async fn move_task_db(pool: &SqlitePool, id: &str, project_id: &str) -> Result<Task> {
+    let project: Option<(String,)> = sqlx::query_as("SELECT id FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+    if project.is_none() {
+        return Err(anyhow::anyhow!("Project not found"));
+    }
+    let task = get_task_db(pool, id).await?;
+    let statuses = get_task_statuses_db(pool, project_id).await?;
+    let status = if statuses.contains(&task.status) { &task.status } else { &statuses[0] };
+    sqlx::query_as::<_, Task>(
+        "UPDATE tasks SET project_id = ?, status = ?, session_id = NULL, updated_at = unixepoch()
+         WHERE id = ? RETURNING *",
+    )
+    .bind(project_id)
+    .bind(status)
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Task not found"))
+}
+
+pub async fn add_task_activity_db(
+    pool: &SqlitePool,
+    task_id: &str,
+    session_id: Option<&str>,
+    kind: &str,
+    content: &str,
+) -> Result<TaskActivity> {
+    let id = Uuid::new_v4().to_string();
+    Ok(sqlx::query_as::<_, TaskActivity>(
+        "INSERT INTO task_activity (id, task_id, session_id, kind, content)
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(&id)
+    .bind(task_id)
+    .bind(session_id)
+    .bind(kind)
+    .bind(content)
+    .fetch_one(pool)
+    .await?)
+}
+
+pub async fn list_task_activity_db(pool: &SqlitePool, task_id: &str) -> Result<Vec<TaskActivity>> {
+    Ok(sqlx::query_as::<_, TaskActivity>(
+        "SELECT * FROM task_activity WHERE task_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// The last `max_lines` non-empty lines of terminal output, with escape sequences removed.
+pub fn transcript_tail(scrollback: &[u8], max_lines: usize) -> String {
+    let text = crate::ansi::strip_ansi(&String::from_utf8_lossy(scrollback));
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim_end_matches('\r').trim_end())
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(max_lines)..].join("\n")
+}
+
+/// Append the tail of a finished session's output to every task linked to it.
+pub async fn record_session_transcript_db(
+    pool: &SqlitePool,
+    session_id: &str,
+    scrollback: &[u8],
+) -> Result<()> {
+    let tasks: Vec<(String,)> = sqlx::query_as("SELECT id FROM tasks WHERE session_id = ?")
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+    if tasks.is_empty() {
+        return Ok(());
+    }
+    let transcript = transcript_tail(scrollback, TRANSCRIPT_LINES);
+    for (task_id,) in tasks {
+        add_task_activity_db(pool, &task_id, Some(session_id), "session_exited", &transcript).await?;
+    }
+    Ok(())
+}
+
+/// A `- [ ] item` / `- [x] item` line (also `*` bullets and indented items): the byte offset
+/// of the box's mark within the line, whether it is checked, and the item's title.
+fn checklist_item(line: &str) -> Option<(usize, bool, &str)> {
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix("- ").or_else(|| rest.strip_prefix("* "))?;
+    let mark = line.len() - rest.len() + 1;
+    let (checked, title) = if let Some(t) = rest.strip_prefix("[ ]") {
+        (false, t)
+    } else if let Some(t) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, t)
+    } else {
+        return None;
+    };
+    let title = title.trim();
+    (!title.is_empty()).then_some((mark, checked, title))
+}
+
+/// Checklist items in `markdown`, with whether each is checked.
+pub fn parse_checklist(markdown: &str) -> Vec<(String, bool)> {
+    markdown
+        .lines()
+        .filter_map(checklist_item)
+        .map(|(_, checked, title)| (title.to_string(), checked))
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct Subtask {
+    /// Position among the description's checklist items; what `toggle_task_checkitem` takes.
+    pub index: usize,
+    pub title: String,
+    pub checked: bool,
+}
+
+/// A task with the checklist in its description broken out, for "3/5 subtasks done".
+#[derive(Debug, Serialize)]
+pub struct RenderedTask {
+    pub task: Task,
+    pub subtasks: Vec<Subtask>,
+    pub done: usize,
+    pub total: usize,
+}
+
+pub fn render_task(task: Task) -> RenderedTask {
+    let subtasks: Vec<Subtask> = parse_checklist(task.description.as_deref().unwrap_or(""))
+        .into_iter()
+        .enumerate()
+        .map(|(index, (title, checked))| Subtask { index, title, checked })
+        .collect();
+    RenderedTask {
+        done: subtasks.iter().filter(|s| s.checked).count(),
+        total: subtasks.len(),
+        subtasks,
+        task,
+    }
+}
+
+/// Flip the `index`th checklist item, leaving the rest of the text untouched. `None` if there
+/// is no such item.
+pub fn toggle_checklist_item(markdown: &str, index: usize) -> Option<String> {
+    let mut seen = 0;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        if let Some((mark, checked, _)) = checklist_item(line.trim_end_matches(['\r', '\n'])) {
+            if seen == index {
+                let at = offset + mark;
+                let mut out = String::with_capacity(markdown.len());
+                out.push_str(&markdown[..at]);
+                out.push(if checked { ' ' } else { 'x' });
+                out.push_str(&markdown[at + 1..]);
+                return Some(out);
+            }
+            seen += 1;
+        }
+        offset += line.len();
+    }
+    None
+}
+
+pub async fn toggle_task_checkitem_db(pool: &SqlitePool, id: &str, index: usize) -> Result<RenderedTask> {
+    let task = get_task_db(pool, id).await?;
+    let description = toggle_checklist_item(task.description.as_deref().unwrap_or(""), index)
+        .ok_or_else(|| anyhow::anyhow!("Checklist item not found"))?;
+    let task = sqlx::query_as::<_, Task>(
+        "UPDATE tasks SET description = ?, updated_at = unixepoch() WHERE id = ? RETURNING *",
+    )
+    .bind(description)
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+    Ok(render_task(task))
+}
+
+/// Create tasks for checklist items whose title isn't already on the project's board.
+/// Unchecked items land in the first status column, checked ones in "done" (or the last column).
+pub async fn import_checklist_db(
+    pool: &SqlitePool,
+    project_id: &str,
+    items: &[(String, bool)],
+) -> Result<Vec<Task>> {
+    let mut titles: std::collections::HashSet<String> = list_tasks_db(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|t| t.title)
+        .collect();
+    let statuses = get_task_statuses_db(pool, project_id).await?;
+    let open = statuses.first().cloned();
+    let done = if statuses.iter().any(|s| s == "done") {
+        Some("done".to_string())
+    } else {
+        statuses.last().cloned()
+    };
+
+    let mut created = Vec::new();
+    for (title, checked) in items {
+        if !titles.insert(title.clone()) {
+            continue;
+        }
+        let status = if *checked { done.as_deref() } else { open.as_deref() };
+        created.push(create_task_db(pool, project_id, title, None, status).await?);
+    }
+    Ok(created)
+}
+
 pub async fn delete_task_db(pool: &SqlitePool, id: &str) -> Result<()> {
     sqlx::query("DELETE FROM tasks WHERE id = ?")
         .bind(id)
@@ -65,12 +438,23 @@ pub async fn delete_task_db(pool: &SqlitePool, id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Payload of `tasks-changed`, emitted after any command that writes a project's tasks so
+/// every window can refetch them.
+#[derive(Debug, Clone, Serialize)]
+pub struct TasksChanged {
+    pub project_id: String,
+}
+
+fn emit_tasks_changed(app: &tauri::AppHandle, project_id: &str) {
+    let _ = app.emit("tasks-changed", TasksChanged { project_id: project_id.to_string() });
+}
+
 #[tauri::command]
 pub async fn list_tasks(
     project_id: String,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Vec<Task>, String> {
-    list_tasks_db(&state.db, &project_id)
+    list_tasks_db(&state.db(), &project_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -80,32 +464,187 @@ pub async fn create_task(
     project_id: String,
     title: String,
     description: Option<String>,
+    status: Option<String>,
+    app: tauri::AppHandle,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<Task, String> {
-    create_task_db(&state.db, &project_id, &title, description.as_deref())
+    let task = create_task_db(
+        &state.db(),
+        &project_id,
+        &title,
+        description.as_deref(),
+        status.as_deref(),
+    )
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    emit_tasks_changed(&app, &project_id);
+    Ok(task)
 }
 
 #[tauri::command]
 pub async fn update_task_status(
     id: String,
     status: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<StatusUpdate, String> {
+    let pool = state.db();
+    let before = get_task_db(&pool, &id).await.map_err(|e| e.to_string())?;
+    update_task_status_db(&pool, &id, &status)
+        .await
+        .map_err(|e| e.to_string())?;
+    emit_tasks_changed(&app, &before.project_id);
+
+    let mut update = StatusUpdate::default();
+    let Some(number) = issue_to_close(&before, &status) else {
+        return Ok(update);
+    };
+    let enabled = crate::settings::get_bool_setting(
+        &pool,
+        crate::settings::CLOSE_ISSUES_ON_DONE,
+        false,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if !enabled {
+        return Ok(update);
+    }
+    let closed = match crate::projects::get_project_path_db(&pool, &before.project_id).await {
+        Ok(path) => {
+            crate::github::close_issue_for_project(&before.project_id, &path, number as u64).await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+    match closed {
+        Ok(()) => update.closed_issue = Some(number),
+        Err(e) => update.close_error = Some(e),
+    }
+    Ok(update)
+}
+
+#[tauri::command]
+pub async fn get_task_statuses(
+    project_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<String>, String> {
+    get_task_statuses_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_task_statuses(
+    project_id: String,
+    statuses: Vec<String>,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    update_task_status_db(&state.db, &id, &status)
+    set_task_statuses_db(&state.db(), &project_id, &statuses)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_task_statuses(
+    project_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<String>, String> {
+    list_task_statuses_db(&state.db(), &project_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_task(
+    id: String,
+    project_id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Task, String> {
+    let pool = state.db();
+    let before = get_task_db(&pool, &id).await.map_err(|e| e.to_string())?;
+    let task = move_task_db(&pool, &id, &project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Both boards change: the task leaves one and joins the other.
+    emit_tasks_changed(&app, &before.project_id);
+    if before.project_id != task.project_id {
+        emit_tasks_changed(&app, &task.project_id);
+    }
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn list_task_activity(
+    task_id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<TaskActivity>, String> {
+    list_task_activity_db(&state.db(), &task_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create tasks from the checklist in `filename` (default `.spawn.md`); returns the new tasks.
+#[tauri::command]
+pub async fn import_tasks_from_markdown(
+    project_id: String,
+    project_path: String,
+    filename: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<Task>, String> {
+    let filename = filename.unwrap_or_else(|| ".spawn.md".to_string());
+    let markdown = crate::projects::read_markdown_file(project_path, filename.clone(), None)?
+        .ok_or_else(|| format!("{} not found", filename))?;
+    let created = import_checklist_db(&state.db(), &project_id, &parse_checklist(&markdown))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !created.is_empty() {
+        emit_tasks_changed(&app, &project_id);
+    }
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn get_task_rendered(
+    id: String,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<RenderedTask, String> {
+    get_task_db(&state.db(), &id)
         .await
+        .map(render_task)
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn toggle_task_checkitem(
+    id: String,
+    index: usize,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<RenderedTask, String> {
+    let rendered = toggle_task_checkitem_db(&state.db(), &id, index)
+        .await
+        .map_err(|e| e.to_string())?;
+    emit_tasks_changed(&app, &rendered.task.project_id);
+    Ok(rendered)
+}
+
 #[tauri::command]
 pub async fn delete_task(
     id: String,
+    app: tauri::AppHandle,
     state: tauri::State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    delete_task_db(&state.db, &id)
+    let pool = state.db();
+    // Deleting an unknown id stays a no-op, just without an event.
+    let project_id = get_task_db(&pool, &id).await.ok().map(|t| t.project_id);
+    delete_task_db(&pool, &id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if let Some(project_id) = project_id {
+        emit_tasks_changed(&app, &project_id);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -124,13 +663,80 @@ mod tests {
             .await
             .unwrap();
 
-        create_task_db(&pool, "p1", "Fix bug", None).await.unwrap();
+        create_task_db(&pool, "p1", "Fix bug", None, None).await.unwrap();
         let tasks = list_tasks_db(&pool, "p1").await.unwrap();
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].title, "Fix bug");
         assert_eq!(tasks[0].status, "todo");
     }
 
+    #[tokio::test]
+    async fn test_create_task_with_initial_status() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let t = create_task_db(&pool, "p1", "Started", None, Some("in_progress"))
+            .await
+            .unwrap();
+        assert_eq!(t.status, "in_progress");
+    }
+
+    #[tokio::test]
+    async fn test_imported_issue_numbers() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','A','/a'), ('p2','B','/b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, source, title, github_issue_number) VALUES
+             ('t1', 'p1', 'github', 'Imported', 7), ('t2', 'p2', 'github', 'Elsewhere', 8)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        create_task_db(&pool, "p1", "Custom", None, None).await.unwrap();
+
+        let imported = imported_issue_numbers_db(&pool, "p1").await.unwrap();
+        assert_eq!(imported.into_iter().collect::<Vec<_>>(), [7]);
+    }
+
+    #[tokio::test]
+    async fn test_issue_to_close_only_for_github_tasks_entering_done() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, source, title, github_issue_number) VALUES
+             ('t1', 'p1', 'github', 'Imported', 7)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let custom = create_task_db(&pool, "p1", "Custom", None, None).await.unwrap();
+
+        let imported = get_task_db(&pool, "t1").await.unwrap();
+        assert_eq!(issue_to_close(&imported, "done"), Some(7));
+        assert_eq!(issue_to_close(&imported, "in_progress"), None);
+        assert_eq!(issue_to_close(&custom, "done"), None);
+
+        update_task_status_db(&pool, "t1", "done").await.unwrap();
+        let done = get_task_db(&pool, "t1").await.unwrap();
+        assert_eq!(issue_to_close(&done, "done"), None);
+        assert!(get_task_db(&pool, "missing").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_update_task_status() {
         let dir = tempdir().unwrap();
@@ -141,12 +747,222 @@ mod tests {
             .await
             .unwrap();
 
-        let t = create_task_db(&pool, "p1", "Task", None).await.unwrap();
+        let t = create_task_db(&pool, "p1", "Task", None, None).await.unwrap();
         update_task_status_db(&pool, &t.id, "done").await.unwrap();
         let tasks = list_tasks_db(&pool, "p1").await.unwrap();
         assert_eq!(tasks[0].status, "done");
     }
 
+    #[tokio::test]
+    async fn test_custom_statuses_validate_updates() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(get_task_statuses_db(&pool, "p1").await.unwrap(), DEFAULT_TASK_STATUSES);
+
+        let columns: Vec<String> = ["backlog", "doing", "review", "done"].map(String::from).to_vec();
+        set_task_statuses_db(&pool, "p1", &columns).await.unwrap();
+        assert_eq!(get_task_statuses_db(&pool, "p1").await.unwrap(), columns);
+
+        let t = create_task_db(&pool, "p1", "Task", None, Some("backlog")).await.unwrap();
+        assert!(create_task_db(&pool, "p1", "Task", None, Some("todo")).await.is_err());
+        let first = create_task_db(&pool, "p1", "Task", None, None).await.unwrap();
+        assert_eq!(first.status, "backlog");
+        update_task_status_db(&pool, &t.id, "review").await.unwrap();
+        assert!(update_task_status_db(&pool, &t.id, "in_progress").await.is_err());
+        assert!(update_task_status_db(&pool, "missing", "done").await.is_err());
+
+        assert!(set_task_statuses_db(&pool, "p1", &[]).await.is_err());
+        assert!(set_task_statuses_db(&pool, "p1", &["a".into(), "a".into()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_task_statuses_appends_statuses_in_use() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(list_task_statuses_db(&pool, "p1").await.unwrap(), DEFAULT_TASK_STATUSES);
+
+        let custom = ["backlog".to_string(), "review".to_string(), "shipped".to_string()];
+        set_task_statuses_db(&pool, "p1", &custom).await.unwrap();
+        create_task_db(&pool, "p1", "a", None, Some("review")).await.unwrap();
+        sqlx::query("UPDATE tasks SET status = 'blocked' WHERE project_id = 'p1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        create_task_db(&pool, "p1", "b", None, Some("review")).await.unwrap();
+        assert_eq!(
+            list_task_statuses_db(&pool, "p1").await.unwrap(),
+            ["backlog", "review", "shipped", "blocked"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_counts_include_empty_columns() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        set_task_statuses_db(&pool, "p1", &["todo".to_string(), "done".to_string()]).await.unwrap();
+        create_task_db(&pool, "p1", "a", None, None).await.unwrap();
+        create_task_db(&pool, "p1", "b", None, None).await.unwrap();
+        let c = create_task_db(&pool, "p1", "c", None, None).await.unwrap();
+        sqlx::query("UPDATE tasks SET status = 'blocked' WHERE id = ?")
+            .bind(&c.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let counts = task_counts_db(&pool, "p1").await.unwrap();
+        let counts: Vec<(&str, usize)> = counts.iter().map(|(s, n)| (s.as_str(), *n)).collect();
+        assert_eq!(counts, [("blocked", 1), ("done", 0), ("todo", 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_move_task_between_projects() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','A','/a'), ('p2','B','/b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let t = create_task_db(&pool, "p1", "Misfiled", None, None).await.unwrap();
+        let moved = move_task_db(&pool, &t.id, "p2").await.unwrap();
+        assert_eq!(moved.project_id, "p2");
+        assert_eq!(moved.created_at, t.created_at);
+        assert!(list_tasks_db(&pool, "p1").await.unwrap().is_empty());
+        assert_eq!(list_tasks_db(&pool, "p2").await.unwrap().len(), 1);
+
+        assert!(move_task_db(&pool, &t.id, "missing").await.is_err());
+        assert!(move_task_db(&pool, "missing", "p1").await.is_err());
+
+        set_task_statuses_db(&pool, "p1", &["backlog".to_string(), "done".to_string()]).await.unwrap();
+        update_task_status_db(&pool, &t.id, "in_progress").await.unwrap();
+        assert_eq!(move_task_db(&pool, &t.id, "p1").await.unwrap().status, "backlog");
+        let done = create_task_db(&pool, "p2", "Done", None, Some("done")).await.unwrap();
+        assert_eq!(move_task_db(&pool, &done.id, "p1").await.unwrap().status, "done");
+    }
+
+    #[test]
+    fn test_transcript_tail_strips_ansi_and_blank_lines() {
+        let out = b"\x1b[32mone\x1b[0m\r\n\r\ntwo  \r\nthree\r\n";
+        assert_eq!(transcript_tail(out, 2), "two\nthree");
+        assert_eq!(transcript_tail(out, 10), "one\ntwo\nthree");
+    }
+
+    #[tokio::test]
+    async fn test_session_transcript_recorded_for_linked_tasks() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let s = crate::sessions::create_session_db(&pool, "p1", "agent").await.unwrap();
+        let linked = create_task_db(&pool, "p1", "Linked", None, None).await.unwrap();
+        let other = create_task_db(&pool, "p1", "Other", None, None).await.unwrap();
+        sqlx::query("UPDATE tasks SET session_id = ? WHERE id = ?")
+            .bind(&s.id)
+            .bind(&linked.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        record_session_transcript_db(&pool, &s.id, b"built\r\ndone\r\n").await.unwrap();
+        let activity = list_task_activity_db(&pool, &linked.id).await.unwrap();
+        assert_eq!(activity.len(), 1);
+        assert_eq!(activity[0].kind, "session_exited");
+        assert_eq!(activity[0].content, "built\ndone");
+        assert_eq!(activity[0].session_id.as_deref(), Some(s.id.as_str()));
+        assert!(list_task_activity_db(&pool, &other.id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_checklist() {
+        let md = "# Notes\n- [ ] Write docs\n  - [x] Fix CI\n* [X] Ship\n- plain item\n- [ ]   \n";
+        assert_eq!(
+            parse_checklist(md),
+            [
+                ("Write docs".to_string(), false),
+                ("Fix CI".to_string(), true),
+                ("Ship".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_checklist_item_rewrites_only_that_box() {
+        let md = "Intro\r\n- [ ] one\r\n  * [X] two\r\n- [ ]\r\n- [ ] three";
+        assert_eq!(
+            toggle_checklist_item(md, 1).unwrap(),
+            "Intro\r\n- [ ] one\r\n  * [ ] two\r\n- [ ]\r\n- [ ] three"
+        );
+        // The empty item isn't counted, matching parse_checklist.
+        assert_eq!(
+            toggle_checklist_item(md, 2).unwrap(),
+            "Intro\r\n- [ ] one\r\n  * [X] two\r\n- [ ]\r\n- [x] three"
+        );
+        assert!(toggle_checklist_item(md, 3).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_toggle_task_checkitem_updates_counts() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let task = create_task_db(&pool, "p1", "T", Some("- [x] a\n- [ ] b\n- [ ] c"), None)
+            .await
+            .unwrap();
+
+        let rendered = render_task(task.clone());
+        assert_eq!((rendered.done, rendered.total), (1, 3));
+
+        let rendered = toggle_task_checkitem_db(&pool, &task.id, 2).await.unwrap();
+        assert_eq!((rendered.done, rendered.total), (2, 3));
+        assert!(rendered.subtasks[2].checked);
+        assert_eq!(rendered.task.description.as_deref(), Some("- [x] a\n- [ ] b\n- [x] c"));
+
+        let err = toggle_task_checkitem_db(&pool, &task.id, 3).await.unwrap_err();
+        assert_eq!(err.to_string(), "Checklist item not found");
+    }
+
+    #[tokio::test]
+    async fn test_import_checklist_skips_existing_titles() {
+        let dir = tempdir().unwrap();
+        let pool = db::init(dir.path()).await.unwrap();
+
+        sqlx::query("INSERT INTO projects (id, name, path) VALUES ('p1','T','/t')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        create_task_db(&pool, "p1", "Existing", None, None).await.unwrap();
+
+        let items = parse_checklist("- [ ] Existing\n- [ ] New\n- [x] Finished\n- [ ] New\n");
+        let created = import_checklist_db(&pool, "p1", &items).await.unwrap();
+        let summary: Vec<(&str, &str)> =
+            created.iter().map(|t| (t.title.as_str(), t.status.as_str())).collect();
+        assert_eq!(summary, [("New", "todo"), ("Finished", "done")]);
+
+        assert!(import_checklist_db(&pool, "p1", &items).await.unwrap().is_empty());
+        assert_eq!(list_tasks_db(&pool, "p1").await.unwrap().len(), 3);
+    }
+
     #[tokio::test]
     async fn test_delete_task() {
         let dir = tempdir().unwrap();
@@ -157,7 +973,7 @@ mod tests {
             .await
             .unwrap();
 
-        let t = create_task_db(&pool, "p1", "Task to delete", None).await.unwrap();
+        let t = create_task_db(&pool, "p1", "Task to delete", None, None).await.unwrap();
         delete_task_db(&pool, &t.id).await.unwrap();
         let tasks = list_tasks_db(&pool, "p1").await.unwrap();
         assert_eq!(tasks.len(), 0);
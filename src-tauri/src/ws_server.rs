@@ -9,7 +9,10 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
+
+/// Port the server listens on unless restarted on another one.
+pub const DEFAULT_PORT: u16 = 9731;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -25,28 +28,69 @@ pub fn server_addr(port: u16) -> SocketAddr {
     SocketAddr::from(([127, 0, 0, 1], port))
 }
 
-pub async fn start(port: u16, terminal_tx: broadcast::Sender<(String, Vec<u8>)>) -> anyhow::Result<()> {
+#[derive(Clone)]
+struct WsState {
+    terminal_tx: broadcast::Sender<(String, Vec<u8>)>,
+    shutdown: watch::Receiver<bool>,
+}
+
+/// A running server. `stop` shuts it down; dropping the handle leaves it running.
+pub struct WsServer {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+    task: tokio::task::JoinHandle<anyhow::Result<()>>,
+}
+
+impl WsServer {
+    /// The bound port, which differs from the requested one when that was 0.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Stop accepting connections, send every client a close frame and wait for the
+    /// server to finish.
+    pub async fn stop(self) -> anyhow::Result<()> {
+        let _ = self.shutdown.send(true);
+        self.task
+            .await
+            .map_err(|e| anyhow::anyhow!("WebSocket server task failed: {}", e))?
+    }
+}
+
+/// Bind and serve in the background. Binding happens before this returns, so a port that
+/// is already taken is reported here.
+pub async fn start(port: u16, terminal_tx: broadcast::Sender<(String, Vec<u8>)>) -> anyhow::Result<WsServer> {
+    let (shutdown, shutdown_rx) = watch::channel(false);
     let app = Router::new()
         .route("/ws", get(ws_handler))
-        .with_state(terminal_tx);
+        .with_state(WsState { terminal_tx, shutdown: shutdown_rx.clone() });
 
     let addr = server_addr(port);
     let listener = tokio::net::TcpListener::bind(addr).await
         .map_err(|e| anyhow::anyhow!("Failed to bind WebSocket server on port {}: {}", port, e))?;
-    axum::serve(listener, app).await
-        .map_err(|e| anyhow::anyhow!("WebSocket server error: {}", e))?;
-    Ok(())
+    let port = listener.local_addr()?.port();
+    let mut stopped = shutdown_rx;
+    let task = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move { wait_for_stop(&mut stopped).await })
+            .await
+            .map_err(|e| anyhow::anyhow!("WebSocket server error: {}", e))
+    });
+    Ok(WsServer { port, shutdown, task })
+}
+
+/// Resolves once `stop` has been called (or the handle is gone).
+async fn wait_for_stop(shutdown: &mut watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stop| *stop).await;
 }
 
-async fn ws_handler(
-    ws: WebSocketUpgrade,
-    State(tx): State<broadcast::Sender<(String, Vec<u8>)>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, tx))
+async fn ws_handler(ws: WebSocketUpgrade, State(state): State<WsState>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
 }
 
-async fn handle_socket(mut socket: WebSocket, tx: broadcast::Sender<(String, Vec<u8>)>) {
-    let mut rx = tx.subscribe();
+async fn handle_socket(mut socket: WebSocket, state: WsState) {
+    let mut rx = state.terminal_tx.subscribe();
+    let mut shutdown = state.shutdown;
 
     loop {
         tokio::select! {
@@ -55,7 +99,7 @@ async fn handle_socket(mut socket: WebSocket, tx: broadcast::Sender<(String, Vec
                     Ok((session_id, data)) => {
                         let msg = WsMessage::TerminalOutput { session_id, data };
                         if let Ok(json) = serde_json::to_string(&msg) {
-                            if socket.send(Message::Text(json.into())).await.is_err() {
+                            if socket.send(Message::Text(json)).await.is_err() {
                                 break;
                             }
                         }
@@ -70,10 +114,51 @@ async fn handle_socket(mut socket: WebSocket, tx: broadcast::Sender<(String, Vec
                     _ => {}
                 }
             }
+            _ = wait_for_stop(&mut shutdown) => {
+                // Upgraded sockets outlive graceful shutdown, so close them explicitly.
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
         }
     }
 }
 
+// --- Tauri commands ---
+
+/// Whether a server was running.
+#[tauri::command]
+pub async fn stop_ws_server(state: tauri::State<'_, crate::AppState>) -> Result<bool, String> {
+    let Some(server) = state.ws_server.lock().await.take() else {
+        return Ok(false);
+    };
+    server.stop().await.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Stop the server if it is running and start it again, on `port` if given, otherwise on
+/// the port it last used. Returns the port now in use.
+#[tauri::command]
+pub async fn restart_ws_server(
+    port: Option<u16>,
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<u16, String> {
+    let mut slot = state.ws_server.lock().await;
+    let previous_port = match slot.take() {
+        Some(server) => {
+            let port = server.port();
+            server.stop().await.map_err(|e| e.to_string())?;
+            port
+        }
+        None => DEFAULT_PORT,
+    };
+    let server = start(port.unwrap_or(previous_port), state.terminal_tx.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    let port = server.port();
+    *slot = Some(server);
+    Ok(port)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,4 +174,17 @@ mod tests {
         let addr = server_addr(9731);
         assert!(addr.ip().is_loopback());
     }
+
+    #[tokio::test]
+    async fn test_stop_releases_port() {
+        let (tx, _) = broadcast::channel(16);
+        let server = start(0, tx.clone()).await.unwrap();
+        let port = server.port();
+        assert_ne!(port, 0);
+        assert!(start(port, tx.clone()).await.is_err());
+
+        server.stop().await.unwrap();
+        let restarted = start(port, tx).await.unwrap();
+        restarted.stop().await.unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 import { useEffect, useRef, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { Sidebar } from './components/Sidebar'
 import { TerminalPane } from './components/TerminalPane'
 import { ShellPane } from './components/ShellPane'
@@ -18,6 +19,7 @@ export default function App() {
     selectedProjectId,
     load: loadProjects,
     add: addProject,
+    addFromGithubUrl: addProjectFromGithubUrl,
     select,
   } = useProjectStore()
 
@@ -66,6 +68,48 @@ export default function App() {
     loadGroups()
   }, [])
 
+  // A watched project's folder was moved or deleted; reload so the sidebar flags it
+  useEffect(() => {
+    const unlisten = listen('project-path-missing', () => loadProjects())
+    return () => {
+      unlisten.then((f) => f())
+    }
+  }, [])
+
+  // Desktop notification for session exits the backend's notification rules let through
+  useEffect(() => {
+    const unlisten = listen<{ project_id: string; exit_code: number | null }>(
+      'session-notification',
+      ({ payload }) => {
+        if (typeof Notification === 'undefined' || Notification.permission === 'denied') return
+        const project = useProjectStore
+          .getState()
+          .projects.find((p) => p.project.id === payload.project_id)
+        const title = project ? `${project.project.name}: session exited` : 'Session exited'
+        const body = payload.exit_code === 0 ? 'Finished successfully' : `Exit status ${payload.exit_code ?? 'unknown'}`
+        if (Notification.permission === 'granted') {
+          new Notification(title, { body })
+        } else {
+          Notification.requestPermission().then((p) => p === 'granted' && new Notification(title, { body }))
+        }
+      },
+    )
+    return () => {
+      unlisten.then((f) => f())
+    }
+  }, [])
+
+  // Another window (or a command elsewhere) changed the selected project's tasks
+  useEffect(() => {
+    if (!selectedProjectId) return
+    const unlisten = listen<{ project_id: string }>('tasks-changed', ({ payload }) => {
+      if (payload.project_id === selectedProjectId) loadTasks(selectedProjectId)
+    })
+    return () => {
+      unlisten.then((f) => f())
+    }
+  }, [selectedProjectId])
+
   // Load sessions and tasks when project selection changes; manage shell terminal
   useEffect(() => {
     if (selectedProjectId) {
@@ -130,6 +174,13 @@ export default function App() {
         config.name,
         config.command,
         args,
+        {
+          agentConfigId: config.id,
+          completionMarker: config.completion_marker,
+          sentinelFile: config.sentinel_file,
+          clearEnv: config.clear_env,
+          outputFilter: config.output_filter,
+        },
       )
     } catch (e) {
       console.error('spawn_agent failed:', e)
@@ -154,12 +205,28 @@ export default function App() {
     setShowAddProject(false)
   }
 
+  const handleAddFromGithubUrl = async (url: string) => {
+    let result = await addProjectFromGithubUrl(url)
+    if (result.status === 'not_found') {
+      if (!result.clone_path) {
+        throw new Error('No local clone found. Set project search directories to find or clone repositories.')
+      }
+      if (!window.confirm(`No local clone found. Clone ${result.clone_url} into ${result.clone_path}?`)) return
+      result = await addProjectFromGithubUrl(url, true)
+    }
+    if (result.status === 'added') {
+      select(result.project.id)
+      setShowAddProject(false)
+    }
+  }
+
   return (
     <div className="flex h-screen bg-zinc-900 text-zinc-100 overflow-hidden">
       {/* Modals */}
       {showAddProject && (
         <AddProjectModal
           onAdd={handleAddProject}
+          onAddFromGithubUrl={handleAddFromGithubUrl}
           onClose={() => setShowAddProject(false)}
         />
       )}
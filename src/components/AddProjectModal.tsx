@@ -3,13 +3,27 @@ import { open } from '@tauri-apps/plugin-dialog'
 
 interface Props {
   onAdd: (path: string, name: string, description?: string) => void
+  // Adds a project from a GitHub URL (finding or cloning it); rejects with a message
+  onAddFromGithubUrl?: (url: string) => Promise<void>
   onClose: () => void
 }
 
-export function AddProjectModal({ onAdd, onClose }: Props) {
+export function AddProjectModal({ onAdd, onAddFromGithubUrl, onClose }: Props) {
   const [path, setPath] = useState('')
   const [name, setName] = useState('')
   const [description, setDescription] = useState('')
+  const [githubUrl, setGithubUrl] = useState('')
+  const [githubError, setGithubError] = useState<string | null>(null)
+
+  const addFromGithubUrl = async () => {
+    if (!onAddFromGithubUrl) return
+    setGithubError(null)
+    try {
+      await onAddFromGithubUrl(githubUrl.trim())
+    } catch (e) {
+      setGithubError(String(e))
+    }
+  }
 
   const pickFolder = async () => {
     const selected = await open({ directory: true, multiple: false })
@@ -81,6 +95,30 @@ export function AddProjectModal({ onAdd, onClose }: Props) {
             />
           </div>
         </div>
+        {onAddFromGithubUrl && (
+          <div className="mt-4 pt-3 border-t border-zinc-700">
+            <label className="block text-xs text-zinc-400 mb-1" htmlFor="project-github-url">
+              Or from a GitHub URL
+            </label>
+            <div className="flex gap-2">
+              <input
+                id="project-github-url"
+                className="flex-1 bg-zinc-700 text-zinc-100 rounded px-3 py-2 text-sm focus:outline-none"
+                placeholder="https://github.com/owner/repo"
+                value={githubUrl}
+                onChange={(e) => setGithubUrl(e.target.value)}
+              />
+              <button
+                disabled={!githubUrl.trim()}
+                onClick={addFromGithubUrl}
+                className="px-3 py-2 text-sm bg-zinc-600 rounded hover:bg-zinc-500 text-zinc-100 disabled:opacity-40"
+              >
+                Open
+              </button>
+            </div>
+            {githubError && <p className="mt-1 text-xs text-red-400 break-all">{githubError}</p>}
+          </div>
+        )}
         <div className="flex gap-2 mt-5 justify-end">
           <button
             onClick={onClose}
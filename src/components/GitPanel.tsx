@@ -1,5 +1,12 @@
 import { useEffect, useState, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+interface BranchInfo {
+  name: string
+  is_current: boolean
+  has_upstream: boolean
+}
 
 interface GitStatus {
   is_git_repo: boolean
@@ -11,16 +18,38 @@ interface GitStatus {
   staged_files: number
   last_commit: string | null
   local_branches: string[]
+  branches: BranchInfo[]
+  error: string | null
+}
+
+interface BranchSwitch {
+  branch: string
+  previous: string | null
+  created: boolean
+}
+
+function describeSwitch({ branch, previous, created }: BranchSwitch): string {
+  const verb = created ? 'Created and switched to' : 'Switched to'
+  return previous ? `${verb} ${branch} (was ${previous})` : `${verb} ${branch}`
+}
+
+interface GitProgress {
+  operation_id: string
+  line: string
 }
 
 interface Props {
   projectPath: string
+  // Enables seeding the commit box from the project's commit template
+  projectId?: string
 }
 
-export function GitPanel({ projectPath }: Props) {
+export function GitPanel({ projectPath, projectId }: Props) {
   const [status, setStatus] = useState<GitStatus | null>(null)
   const [loading, setLoading] = useState(false)
   const [actionError, setActionError] = useState<string | null>(null)
+  const [actionNotice, setActionNotice] = useState<string | null>(null)
+  const [progressLine, setProgressLine] = useState<string | null>(null)
   const [showBranchPicker, setShowBranchPicker] = useState(false)
   const [newBranchName, setNewBranchName] = useState('')
   const [commitMsg, setCommitMsg] = useState('')
@@ -36,8 +65,21 @@ export function GitPanel({ projectPath }: Props) {
     }
   }
 
+  // Start from the configured commit template, if any; the message stays freely editable
+  const seedCommitMsg = async () => {
+    if (!projectId) return
+    try {
+      const template = await invoke<string | null>('get_commit_template', { projectId, projectPath })
+      setCommitMsg((current) => current || (template ?? ''))
+    } catch (e) {
+      console.error('get_commit_template failed:', e)
+    }
+  }
+
   useEffect(() => {
     load()
+    setCommitMsg('')
+    seedCommitMsg()
   }, [projectPath])
 
   // Close branch picker on outside click
@@ -55,6 +97,7 @@ export function GitPanel({ projectPath }: Props) {
 
   const runAction = async (action: () => Promise<unknown>) => {
     setActionError(null)
+    setActionNotice(null)
     setLoading(true)
     try {
       await action()
@@ -66,10 +109,37 @@ export function GitPanel({ projectPath }: Props) {
     }
   }
 
+  // Pull/push stream their output as 'git-progress' events; show the latest line meanwhile.
+  // A branch without an upstream is pushed with `-u origin <branch>`; lines the remote
+  // printed (e.g. a pull request link) are shown once it finishes.
+  const runNetworkAction = (command: 'git_pull' | 'git_push') => {
+    const operationId = crypto.randomUUID()
+    const setUpstream = command === 'git_push' && !!status?.branch && !status.has_upstream
+    runAction(async () => {
+      const unlisten = await listen<GitProgress>('git-progress', (e) => {
+        if (e.payload.operation_id === operationId) setProgressLine(e.payload.line)
+      })
+      try {
+        const output = await invoke<string>(command, { projectPath, operationId, setUpstream })
+        const remoteLines = output
+          .split('\n')
+          .filter((l) => l.startsWith('remote:'))
+          .map((l) => l.slice('remote:'.length).trim())
+          .filter(Boolean)
+        if (remoteLines.length > 0) setActionNotice(remoteLines.join('\n'))
+      } finally {
+        unlisten()
+        setProgressLine(null)
+      }
+    })
+  }
+
   const handleCheckout = (branch: string) => {
     setShowBranchPicker(false)
     setNewBranchName('')
-    runAction(() => invoke('git_checkout', { projectPath, branch }))
+    runAction(async () => {
+      setActionNotice(describeSwitch(await invoke<BranchSwitch>('git_checkout', { projectPath, branch })))
+    })
   }
 
   const handleCreateBranch = () => {
@@ -77,7 +147,9 @@ export function GitPanel({ projectPath }: Props) {
     if (!name) return
     setShowBranchPicker(false)
     setNewBranchName('')
-    runAction(() => invoke('git_create_branch', { projectPath, branch: name }))
+    runAction(async () => {
+      setActionNotice(describeSwitch(await invoke<BranchSwitch>('git_create_branch', { projectPath, branch: name })))
+    })
   }
 
   const handleCommitAll = async () => {
@@ -89,6 +161,7 @@ export function GitPanel({ projectPath }: Props) {
       await invoke('git_commit_all', { projectPath, message: msg })
       setCommitMsg('')
       await load()
+      await seedCommitMsg()
     } catch (e) {
       setActionError(String(e))
     } finally {
@@ -104,14 +177,20 @@ export function GitPanel({ projectPath }: Props) {
         <div className="text-xs font-semibold text-zinc-400 uppercase tracking-wider mb-2">
           Git
         </div>
-        <p className="text-xs text-zinc-500 mb-2">Not a git repository</p>
-        <button
-          onClick={() => runAction(() => invoke('git_init', { projectPath }))}
-          disabled={loading}
-          className="text-xs px-2 py-1 rounded bg-zinc-700 hover:bg-zinc-600 text-zinc-200 transition-colors disabled:opacity-50"
-        >
-          git init
-        </button>
+        {status.error ? (
+          <p className="text-xs text-red-400 break-all">Can't read repository: {status.error}</p>
+        ) : (
+          <>
+            <p className="text-xs text-zinc-500 mb-2">Not a git repository</p>
+            <button
+              onClick={() => runAction(() => invoke('git_init', { projectPath }))}
+              disabled={loading}
+              className="text-xs px-2 py-1 rounded bg-zinc-700 hover:bg-zinc-600 text-zinc-200 transition-colors disabled:opacity-50"
+            >
+              git init
+            </button>
+          </>
+        )}
         {actionError && (
           <p className="mt-1 text-xs text-red-400 break-all">{actionError}</p>
         )}
@@ -189,14 +268,14 @@ export function GitPanel({ projectPath }: Props) {
           </span>
         )}
         <button
-          onClick={() => runAction(() => invoke('git_pull', { projectPath }))}
+          onClick={() => runNetworkAction('git_pull')}
           disabled={loading}
           className="text-xs px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 text-zinc-200 transition-colors disabled:opacity-50"
         >
           Pull
         </button>
         <button
-          onClick={() => runAction(() => invoke('git_push', { projectPath }))}
+          onClick={() => runNetworkAction('git_push')}
           disabled={loading}
           className="text-xs px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 text-zinc-200 transition-colors disabled:opacity-50"
         >
@@ -253,6 +332,12 @@ export function GitPanel({ projectPath }: Props) {
         </p>
       )}
 
+      {progressLine && (
+        <p className="mt-1 text-xs text-zinc-400 truncate font-mono">{progressLine}</p>
+      )}
+      {actionNotice && !actionError && (
+        <p className="mt-1 text-xs text-green-400 break-all whitespace-pre-line">{actionNotice}</p>
+      )}
       {actionError && (
         <p className="mt-1 text-xs text-red-400 break-all">{actionError}</p>
       )}
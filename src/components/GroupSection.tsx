@@ -137,7 +137,7 @@ export function GroupSection({
       {/* Projects in group */}
       {isExpanded && (
         <ul>
-          {projects.map(({ project, branch }) => (
+          {projects.map(({ project, branch, path_exists }) => (
             <li
               key={project.id}
               className={`group/item flex items-center pl-6 pr-2 py-2 cursor-pointer hover:bg-zinc-700 transition-colors ${
@@ -147,6 +147,11 @@ export function GroupSection({
             >
               <div className="flex-1 min-w-0">
                 <div className="text-sm text-zinc-100 truncate">{project.name}</div>
+                {!path_exists && (
+                  <div className="text-xs text-red-400 truncate" title={project.path}>
+                    Folder missing
+                  </div>
+                )}
                 {branch && (
                   <div className="text-xs text-zinc-400 truncate">{branch}</div>
                 )}
@@ -15,6 +15,7 @@ export function MarkdownEditor({ projectPath, filename, onClose }: Props) {
     invoke<string | null>('read_markdown_file', {
       projectPath,
       filename,
+      lossy: true,
     }).then((c) => setContent(c ?? ''))
   }, [projectPath, filename])
 
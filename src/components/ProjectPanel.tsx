@@ -95,7 +95,7 @@ export function ProjectPanel({
           </div>
 
           {/* Git section */}
-          <GitPanel projectPath={project.path} />
+          <GitPanel projectPath={project.path} projectId={project.id} />
 
           {/* Files section */}
           <div className="p-3">
@@ -1,4 +1,5 @@
 import { useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import { useAgentConfigStore, type AgentConfig } from '../store/agentConfigs'
 
 interface Props {
@@ -151,6 +152,19 @@ export function SettingsModal({ onClose }: Props) {
   )
 }
 
+interface TrialRun {
+  started: boolean
+  exit_code: number | null
+  output: string
+  error: string | null
+}
+
+function describeTrial(t: TrialRun): string {
+  if (t.error) return `Failed to start: ${t.error}`
+  if (t.exit_code === null) return 'Started and kept running'
+  return t.started ? 'Ran and exited cleanly' : `Exited immediately with status ${t.exit_code}`
+}
+
 interface AgentFormProps {
   form: FormState
   onChange: (f: FormState) => void
@@ -159,6 +173,21 @@ interface AgentFormProps {
 }
 
 function AgentForm({ form, onChange, onSave, onCancel }: AgentFormProps) {
+  const [trial, setTrial] = useState<TrialRun | null>(null)
+  const [testing, setTesting] = useState(false)
+
+  const runTest = async () => {
+    setTesting(true)
+    setTrial(null)
+    try {
+      setTrial(await invoke<TrialRun>('test_agent_config', { command: form.command, args: argsToArray(form.args) }))
+    } catch (e) {
+      setTrial({ started: false, exit_code: null, output: '', error: String(e) })
+    } finally {
+      setTesting(false)
+    }
+  }
+
   const field = (key: keyof FormState) => ({
     value: form[key],
     onChange: (e: React.ChangeEvent<HTMLInputElement>) =>
@@ -195,7 +224,24 @@ function AgentForm({ form, onChange, onSave, onCancel }: AgentFormProps) {
           className="w-full bg-zinc-700 text-zinc-100 text-sm rounded px-2 py-1.5 font-mono focus:outline-none focus:ring-1 focus:ring-blue-500"
         />
       </div>
+      {trial && (
+        <div className={`text-xs ${trial.started ? 'text-green-400' : 'text-red-400'}`}>
+          {describeTrial(trial)}
+          {trial.output && (
+            <pre className="mt-1 max-h-24 overflow-y-auto whitespace-pre-wrap text-zinc-400 font-mono">
+              {trial.output}
+            </pre>
+          )}
+        </div>
+      )}
       <div className="flex gap-2 justify-end pt-1">
+        <button
+          onClick={runTest}
+          disabled={!form.command || testing}
+          className="text-sm text-zinc-400 hover:text-zinc-200 px-3 py-1 mr-auto disabled:opacity-40"
+        >
+          {testing ? 'Testing…' : 'Test'}
+        </button>
         <button
           onClick={onCancel}
           className="text-sm text-zinc-400 hover:text-zinc-200 px-3 py-1"
@@ -7,12 +7,14 @@ const mockProjects = [
     branch: 'main',
     last_commit: 'fix bug',
     has_agenthub_md: true,
+    path_exists: true,
   },
   {
     project: { id: '2', name: 'ProjectB', path: '/b', created_at: 0 },
     branch: null,
     last_commit: null,
     has_agenthub_md: false,
+    path_exists: true,
   },
 ]
 
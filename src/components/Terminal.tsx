@@ -9,9 +9,12 @@ interface Props {
   onInput: (data: string) => void
   onReady?: (write: (data: Uint8Array) => void) => void
   scrollback?: Uint8Array
+  // Geometry the session last had, so replayed scrollback doesn't reflow before the fit
+  cols?: number
+  rows?: number
 }
 
-export function Terminal({ sessionId, onInput, onReady, scrollback }: Props) {
+export function Terminal({ sessionId, onInput, onReady, scrollback, cols, rows }: Props) {
   const containerRef = useRef<HTMLDivElement>(null)
 
   useEffect(() => {
@@ -22,6 +25,7 @@ export function Terminal({ sessionId, onInput, onReady, scrollback }: Props) {
       fontFamily: 'JetBrains Mono, Fira Code, monospace',
       fontSize: 13,
       cursorBlink: true,
+      ...(cols && rows ? { cols, rows } : {}),
     })
 
     const fitAddon = new FitAddon()
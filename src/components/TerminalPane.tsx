@@ -136,6 +136,7 @@ export function TerminalPane({ sessions, agentConfigs, onSpawn, onKill, onRename
             <div
               key={s.id}
               onClick={() => setActiveId(s.id)}
+              style={s.color ? { boxShadow: `inset 0 2px 0 ${s.color}` } : undefined}
               className={`flex items-center gap-1.5 px-3 py-2 text-sm rounded-t-sm transition-colors whitespace-nowrap cursor-pointer select-none flex-shrink-0 ${
                 activeId === s.id
                   ? 'bg-zinc-900 text-zinc-100'
@@ -144,8 +145,13 @@ export function TerminalPane({ sessions, agentConfigs, onSpawn, onKill, onRename
             >
               <span
                 className={`w-2 h-2 rounded-full flex-shrink-0 ${
-                  s.status === 'running' ? 'bg-green-400' : 'bg-zinc-500'
+                  s.status === 'running'
+                    ? 'bg-green-400'
+                    : s.status === 'failed_to_start'
+                      ? 'bg-red-400'
+                      : 'bg-zinc-500'
                 }`}
+                title={s.status === 'failed_to_start' ? 'No output since launch' : undefined}
               />
               {editingId === s.id ? (
                 <input
@@ -159,7 +165,7 @@ export function TerminalPane({ sessions, agentConfigs, onSpawn, onKill, onRename
                 />
               ) : (
                 <span onDoubleClick={(e) => startEdit(e, s)} title="Double-click to rename">
-                  {s.name ?? 'Agent'}
+                  {s.name ?? s.terminal_title ?? 'Agent'}
                 </span>
               )}
               <span
@@ -221,6 +227,8 @@ export function TerminalPane({ sessions, agentConfigs, onSpawn, onKill, onRename
           >
             <Terminal
               sessionId={s.id}
+              cols={s.cols}
+              rows={s.rows}
               onInput={(data) => handleInput(s.id, data)}
               onReady={(write) => termWriteRefs.current.set(s.id, write)}
             />
@@ -8,6 +8,14 @@ export interface AgentConfig {
   args: string // JSON array string
   is_default: boolean
   created_at: number
+  position: number
+  // Optional "task finished" signals; either one emits 'session-task-complete'
+  completion_marker: string | null
+  sentinel_file: string | null
+  // Start from PATH, HOME and TERM only instead of inheriting the app's environment
+  clear_env: boolean
+  // Shell command the output is piped through (e.g. `jq --unbuffered .`); raw output if it fails
+  output_filter: string | null
 }
 
 interface AgentConfigStore {
@@ -17,6 +25,10 @@ interface AgentConfigStore {
   update: (id: string, name: string, command: string, args: string[]) => Promise<void>
   remove: (id: string) => Promise<void>
   setDefault: (id: string) => Promise<void>
+  reorder: (id: string, position: number) => Promise<void>
+  setCompletionSignal: (id: string, completionMarker: string | null, sentinelFile: string | null) => Promise<void>
+  setClearEnv: (id: string, clearEnv: boolean) => Promise<void>
+  setOutputFilter: (id: string, outputFilter: string | null) => Promise<void>
 }
 
 export const useAgentConfigStore = create<AgentConfigStore>((set, get) => ({
@@ -45,4 +57,20 @@ export const useAgentConfigStore = create<AgentConfigStore>((set, get) => ({
     await invoke('set_default_agent_config', { id })
     await get().load()
   },
+  reorder: async (id, position) => {
+    await invoke('reorder_agent_config', { id, position })
+    await get().load()
+  },
+  setCompletionSignal: async (id, completionMarker, sentinelFile) => {
+    await invoke('set_agent_completion_signal', { id, completionMarker, sentinelFile })
+    await get().load()
+  },
+  setClearEnv: async (id, clearEnv) => {
+    await invoke('set_agent_clear_env', { id, clearEnv })
+    await get().load()
+  },
+  setOutputFilter: async (id, outputFilter) => {
+    await invoke('set_agent_output_filter', { id, outputFilter })
+    await get().load()
+  },
 }))
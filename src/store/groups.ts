@@ -15,6 +15,8 @@ interface GroupStore {
   rename: (id: string, name: string) => Promise<void>
   remove: (id: string) => Promise<void>
   assignProject: (projectId: string, groupId: string | null) => Promise<void>
+  // Resolves to the number of projects actually moved
+  assignProjects: (projectIds: string[], groupId: string | null) => Promise<number>
 }
 
 export const useGroupStore = create<GroupStore>((set, get) => ({
@@ -39,4 +41,7 @@ export const useGroupStore = create<GroupStore>((set, get) => ({
   assignProject: async (projectId, groupId) => {
     await invoke('assign_project_group', { projectId, groupId })
   },
+  assignProjects: async (projectIds, groupId) => {
+    return invoke<number>('assign_projects_group', { projectIds, groupId })
+  },
 }))
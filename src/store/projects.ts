@@ -8,6 +8,7 @@ export interface Project {
   description?: string
   github_repo?: string
   group_id: string | null
+  theme: string | null
   created_at: number
 }
 
@@ -16,13 +17,34 @@ export interface ProjectWithGit {
   branch: string | null
   last_commit: string | null
   has_spawn_md: boolean
+  // False once the directory has been moved or deleted
+  path_exists: boolean
 }
 
+// Everything the project view needs on open, in one round-trip
+export interface ProjectDashboard {
+  project: ProjectWithGit
+  running_sessions: number
+  // Tasks per board column, empty columns included
+  task_counts: Record<string, number>
+  has_github_token: boolean
+}
+
+export const getProjectDashboard = (id: string) =>
+  invoke<ProjectDashboard>('get_project_dashboard', { id })
+
+export type GithubUrlProject =
+  | { status: 'added'; project: Project }
+  | { status: 'not_found'; clone_url: string; clone_path: string | null }
+
 interface ProjectStore {
   projects: ProjectWithGit[]
   selectedProjectId: string | null
   load: () => Promise<void>
   add: (path: string, name: string, description?: string) => Promise<void>
+  addFromGithubUrl: (url: string, clone?: boolean) => Promise<GithubUrlProject>
+  duplicate: (id: string, path: string, name: string) => Promise<Project>
+  relocate: (id: string, path: string) => Promise<Project>
   remove: (id: string) => Promise<void>
   select: (id: string | null) => void
 }
@@ -38,6 +60,21 @@ export const useProjectStore = create<ProjectStore>((set, get) => ({
     await invoke('add_project', { path, name, description })
     await get().load()
   },
+  addFromGithubUrl: async (url, clone) => {
+    const result = await invoke<GithubUrlProject>('add_project_from_github_url', { url, clone })
+    if (result.status === 'added') await get().load()
+    return result
+  },
+  duplicate: async (id, path, name) => {
+    const project = await invoke<Project>('duplicate_project', { id, path, name })
+    await get().load()
+    return project
+  },
+  relocate: async (id, path) => {
+    const project = await invoke<Project>('relocate_project', { id, path })
+    await get().load()
+    return project
+  },
   remove: async (id) => {
     await invoke('remove_project', { id })
     await get().load()
@@ -7,16 +7,58 @@ export interface AgentSession {
   name: string | null
   status: string
   scrollback: string | null
+  // 'base64' when the saved output wasn't valid UTF-8; decode before use, or call get_scrollback.
+  // 'gzip' when archived: scrollback is the archive's path and only get_scrollback reads it
+  scrollback_encoding: 'utf8' | 'base64' | 'gzip'
   created_at: number
   updated_at: number
+  // Agent config the session was launched from, if it still exists
+  agent_config_id: string | null
+  // Byte totals saved when the session stopped; live values come from session_counters
+  bytes_out: number
+  bytes_in: number
+  // Opaque color hint set via setColor
+  color: string | null
+  // Last terminal size (80x24 until resized); restore it before replaying scrollback
+  cols: number
+  rows: number
+  // Title the running program set via OSC 0/2 (also pushed as 'session-title-changed')
+  terminal_title: string | null
+}
+
+export interface SpawnOptions {
+  agentConfigId?: string | null
+  completionMarker?: string | null
+  sentinelFile?: string | null
+  // Flag the session 'failed_to_start' if it prints nothing for this long; the process is kept
+  startupTimeoutSecs?: number | null
+  // ...unless this is set, in which case it is killed
+  killOnStartupTimeout?: boolean
+  // Don't inherit the app's environment (see AgentConfig.clear_env)
+  clearEnv?: boolean
+  outputFilter?: string | null
 }
 
 interface SessionStore {
   sessions: AgentSession[]
   load: (projectId: string) => Promise<void>
-  spawn: (projectId: string, projectPath: string, agentName: string, command: string, args: string[]) => Promise<AgentSession>
+  spawn: (
+    projectId: string,
+    projectPath: string,
+    agentName: string,
+    command: string,
+    args: string[],
+    options?: SpawnOptions,
+  ) => Promise<AgentSession>
   kill: (sessionId: string) => Promise<void>
+  // Moves a stopped session's scrollback into a gzip file; getScrollback still reads it
+  archiveScrollback: (sessionId: string) => Promise<void>
+  // Stop tracking without killing; the process is never managed or reaped again
+  detach: (sessionId: string) => Promise<void>
   rename: (sessionId: string, name: string) => Promise<void>
+  setColor: (sessionId: string, color: string | null) => Promise<void>
+  // Observer mode: input is rejected while set; not persisted across restarts
+  setReadonly: (sessionId: string, readonly: boolean) => Promise<void>
 }
 
 export const useSessionStore = create<SessionStore>((set, get) => ({
@@ -25,13 +67,14 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     const sessions = await invoke<AgentSession[]>('list_sessions', { projectId })
     set({ sessions })
   },
-  spawn: async (projectId, projectPath, agentName, command, args) => {
+  spawn: async (projectId, projectPath, agentName, command, args, options) => {
     const session = await invoke<AgentSession>('spawn_agent', {
       projectId,
       projectPath,
       agentName,
       command,
       args,
+      ...options,
     })
     await get().load(projectId)
     return session
@@ -40,6 +83,13 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     await invoke('kill_agent', { sessionId })
     set((s) => ({ sessions: s.sessions.filter((x) => x.id !== sessionId) }))
   },
+  archiveScrollback: async (sessionId) => {
+    await invoke('archive_session_scrollback', { sessionId })
+  },
+  detach: async (sessionId) => {
+    await invoke('detach_session', { sessionId })
+    set((s) => ({ sessions: s.sessions.filter((x) => x.id !== sessionId) }))
+  },
   rename: async (sessionId, name) => {
     await invoke('rename_agent', { sessionId, name })
     set((s) => ({
@@ -48,4 +98,15 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
       ),
     }))
   },
+  setReadonly: async (sessionId, readonly) => {
+    await invoke('set_session_readonly', { sessionId, readonly })
+  },
+  setColor: async (sessionId, color) => {
+    await invoke('set_session_color', { sessionId, color })
+    set((s) => ({
+      sessions: s.sessions.map((x) =>
+        x.id === sessionId ? { ...x, color } : x
+      ),
+    }))
+  },
 }))
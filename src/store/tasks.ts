@@ -14,11 +14,28 @@ export interface Task {
   updated_at: number
 }
 
+// A task with the `- [ ]` / `- [x]` checklist in its description broken out.
+export interface Subtask {
+  index: number
+  title: string
+  checked: boolean
+}
+
+export interface RenderedTask {
+  task: Task
+  subtasks: Subtask[]
+  done: number
+  total: number
+}
+
+export const getTaskRendered = (id: string) => invoke<RenderedTask>('get_task_rendered', { id })
+
 interface TaskStore {
   tasks: Task[]
   load: (projectId: string) => Promise<void>
   add: (projectId: string, title: string) => Promise<void>
   updateStatus: (id: string, status: string) => Promise<void>
+  toggleCheckItem: (id: string, index: number) => Promise<RenderedTask>
   remove: (id: string) => Promise<void>
 }
 
@@ -38,6 +55,13 @@ export const useTaskStore = create<TaskStore>((set) => ({
       tasks: s.tasks.map((t) => (t.id === id ? { ...t, status } : t)),
     }))
   },
+  toggleCheckItem: async (id, index) => {
+    const rendered = await invoke<RenderedTask>('toggle_task_checkitem', { id, index })
+    set((s) => ({
+      tasks: s.tasks.map((t) => (t.id === id ? rendered.task : t)),
+    }))
+    return rendered
+  },
   remove: async (id) => {
     await invoke('delete_task', { id })
     set((s) => ({ tasks: s.tasks.filter((t) => t.id !== id) }))